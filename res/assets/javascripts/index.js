@@ -2,6 +2,12 @@ var IndexManager = (function() {
   return {
     _REFRESH_TIMEOUT : 5000,
     _REFRESH_INTERVAL : 20000,
+    _STREAM_DEBOUNCE : 1000,
+
+    _STREAM_SOURCE : null,
+    _STREAM_RELOAD_SCHEDULED : false,
+
+    _PATH_BASE : "/",
 
     _SELECTOR_ASIDE : null,
     _SELECTOR_MAIN : null,
@@ -15,6 +21,67 @@ var IndexManager = (function() {
       );
 
       if (IndexManager._SELECTOR_ASIDE && IndexManager._SELECTOR_MAIN) {
+        // Reload the page that is being viewed (eg. the private status page \
+        //   must not get replaced with the public status page upon refresh).
+        IndexManager._PATH_BASE = window.location.pathname;
+
+        // Prefer live updates from the status stream, and fallback to polling \
+        //   on browsers that do not support server-sent events.
+        if (typeof window.EventSource === "function") {
+          IndexManager.__bind_stream();
+        } else {
+          IndexManager.__schedule_refresh();
+        }
+      }
+    },
+
+    __bind_stream : function() {
+      var source = new EventSource("/status/stream");
+
+      source.addEventListener(
+        "status", IndexManager.__handle_stream_event
+      );
+      source.addEventListener(
+        "announcement_insert", IndexManager.__handle_stream_event
+      );
+      source.addEventListener(
+        "announcement_retract", IndexManager.__handle_stream_event
+      );
+      source.addEventListener(
+        "resync", IndexManager.__handle_stream_event
+      );
+
+      source.onerror = IndexManager.__handle_stream_error;
+
+      IndexManager._STREAM_SOURCE = source;
+    },
+
+    __handle_stream_event : function() {
+      // Debounce reloads, as a single change usually comes as a burst of \
+      //   events (eg. replica, node, service and general status changes).
+      if (IndexManager._STREAM_RELOAD_SCHEDULED !== true) {
+        IndexManager._STREAM_RELOAD_SCHEDULED = true;
+
+        setTimeout(function() {
+          IndexManager._STREAM_RELOAD_SCHEDULED = false;
+
+          IndexManager.__load(
+            IndexManager._PATH_BASE, "document",
+
+            IndexManager.__handle_base_done,
+            IndexManager.__handle_base_error
+          );
+        }, IndexManager._STREAM_DEBOUNCE);
+      }
+    },
+
+    __handle_stream_error : function() {
+      // Stream was closed for good (browsers retry on transient errors), \
+      //   fallback to polling.
+      if (IndexManager._STREAM_SOURCE &&
+            IndexManager._STREAM_SOURCE.readyState === EventSource.CLOSED) {
+        IndexManager._STREAM_SOURCE = null;
+
         IndexManager.__schedule_refresh();
       }
     },
@@ -41,7 +108,7 @@ var IndexManager = (function() {
         window.STATUS_GENERAL = status;
 
         IndexManager.__load(
-          "/", "document",
+          IndexManager._PATH_BASE, "document",
 
           IndexManager.__handle_base_done,
           IndexManager.__handle_base_error
@@ -79,7 +146,10 @@ var IndexManager = (function() {
         }
       }
 
-      IndexManager.__schedule_refresh();
+      // Stream is live? Do not poll.
+      if (IndexManager._STREAM_SOURCE === null) {
+        IndexManager.__schedule_refresh();
+      }
     },
 
     __handle_base_error : function() {
@@ -0,0 +1,82 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use crate::prober::status::Status;
+use crate::prober::visibility::ServiceStatesSummary;
+
+const HISTORY_TRANSITIONS_MAXIMUM: usize = 100;
+
+lazy_static! {
+    pub static ref STORE: Arc<RwLock<Store>> = Arc::new(RwLock::new(Store {
+        transitions: VecDeque::new(),
+        statuses: HashMap::new(),
+        since: SystemTime::now(),
+    }));
+}
+
+pub struct Store {
+    pub transitions: VecDeque<Transition>,
+    pub since: SystemTime,
+
+    statuses: HashMap<String, Status>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Transition {
+    pub id: String,
+    pub probe_id: String,
+    pub probe_label: String,
+    pub from: Status,
+    pub to: Status,
+    pub time: SystemTime,
+}
+
+pub fn record(states: &ServiceStatesSummary) -> Vec<Transition> {
+    let mut store = STORE.write().unwrap();
+
+    let mut transitions = Vec::new();
+
+    // Notice: transitions are recorded from the public view of services, as they are meant to \
+    //   be published (eg. in feeds), and must not reveal non-public nodes failures.
+    for probe in states.probes.iter() {
+        let previous_status = store.statuses.insert(probe.id.to_owned(), probe.status);
+
+        // Append transition? (the first status seen for a service is a baseline)
+        if let Some(previous_status) = previous_status {
+            if previous_status != probe.status {
+                debug!(
+                    "recorded status transition for probe: {} ({:?} => {:?})",
+                    probe.id, previous_status, probe.status
+                );
+
+                transitions.push(Transition {
+                    id: Uuid::new_v4().hyphenated().to_string(),
+                    probe_id: probe.id.to_owned(),
+                    probe_label: probe.label.to_owned(),
+                    from: previous_status,
+                    to: probe.status,
+                    time: SystemTime::now(),
+                });
+            }
+        }
+    }
+
+    store.transitions.extend(transitions.iter().cloned());
+
+    // Retain most recent transitions only
+    while store.transitions.len() > HISTORY_TRANSITIONS_MAXIMUM {
+        store.transitions.pop_front();
+    }
+
+    transitions
+}
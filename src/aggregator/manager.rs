@@ -4,52 +4,27 @@
 // Copyright: 2018, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-use std::iter::FromIterator;
+use std::collections::HashMap;
 use std::thread;
 use std::time::{Duration, SystemTime};
 use time;
 use time::format_description::FormatItem;
 
+use indexmap::IndexMap;
+
+use super::history::record as record_history;
 use crate::config::config::ConfigNotifyReminderBackoffFunction;
-use crate::notifier::generic::Notification;
+use crate::notifier::dispatcher::{dispatch as dispatch_notification, DispatchJob};
+use crate::notifier::generic::NotificationDigest;
 use crate::prober::manager::STORE as PROBER_STORE;
 use crate::prober::mode::Mode;
+use crate::prober::states::{ServiceStatesDependency, ServiceStatesProbe};
 use crate::prober::status::Status;
+use crate::prober::visibility::Visibility;
+use crate::responder::events::publish_states as publish_states_events;
+use crate::subscriber::dispatcher::dispatch_transitions as dispatch_subscriber_transitions;
 use crate::APP_CONF;
 
-#[cfg(feature = "notifier-email")]
-use crate::notifier::email::EmailNotifier;
-
-#[cfg(feature = "notifier-twilio")]
-use crate::notifier::twilio::TwilioNotifier;
-
-#[cfg(feature = "notifier-slack")]
-use crate::notifier::slack::SlackNotifier;
-
-#[cfg(feature = "notifier-zulip")]
-use crate::notifier::zulip::ZulipNotifier;
-
-#[cfg(feature = "notifier-telegram")]
-use crate::notifier::telegram::TelegramNotifier;
-
-#[cfg(feature = "notifier-pushover")]
-use crate::notifier::pushover::PushoverNotifier;
-
-#[cfg(feature = "notifier-gotify")]
-use crate::notifier::gotify::GotifyNotifier;
-
-#[cfg(feature = "notifier-xmpp")]
-use crate::notifier::xmpp::XMPPNotifier;
-
-#[cfg(feature = "notifier-matrix")]
-use crate::notifier::matrix::MatrixNotifier;
-
-#[cfg(feature = "notifier-webex")]
-use crate::notifier::webex::WebExNotifier;
-
-#[cfg(feature = "notifier-webhook")]
-use crate::notifier::webhook::WebHookNotifier;
-
 lazy_static! {
     static ref TIME_NOW_FORMATTER: Vec<FormatItem<'static>> = time::format_description::parse(
         "[hour]:[minute]:[second] UTC[offset_hour sign:mandatory]:[offset_minute]"
@@ -67,6 +42,12 @@ struct BumpedStates {
     startup: bool,
 }
 
+struct PendingDigest {
+    opened: SystemTime,
+    changed: bool,
+    escalated: Option<u16>,
+}
+
 fn check_child_status(parent_status: &Status, child_status: &Status) -> Option<Status> {
     if child_status == &Status::Dead {
         Some(Status::Dead)
@@ -77,7 +58,61 @@ fn check_child_status(parent_status: &Status, child_status: &Status) -> Option<S
     }
 }
 
-fn scan_and_bump_states() -> Option<BumpedStates> {
+fn mark_impacted(probes: &mut IndexMap<String, ServiceStatesProbe>) {
+    // Acquire all current statuses (dependencies are referenced as 'service' or 'service:node')
+    let mut statuses = HashMap::new();
+
+    for (probe_id, probe) in probes.iter() {
+        statuses.insert(probe_id.to_owned(), probe.status);
+
+        for (node_id, node) in probe.nodes.iter() {
+            statuses.insert(format!("{}:{}", probe_id, node_id), node.status);
+        }
+    }
+
+    // Mark failing nodes that depend on a failing upstream as impacted by this upstream
+    for (probe_id, probe) in probes.iter_mut() {
+        let mut probe_impacted_by: Vec<ServiceStatesDependency> = Vec::new();
+
+        for (node_id, node) in probe.nodes.iter_mut() {
+            node.impacted_by = if node.status != Status::Healthy {
+                probe
+                    .depends_on
+                    .iter()
+                    .chain(node.depends_on.iter())
+                    .filter(|dependency| {
+                        statuses
+                            .get(&dependency.id)
+                            .map(|status| status != &Status::Healthy)
+                            .unwrap_or(false)
+                    })
+                    .cloned()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            for dependency in node.impacted_by.iter() {
+                debug!(
+                    "node: {}:{} is impacted by upstream: {}",
+                    probe_id, node_id, dependency.id
+                );
+
+                let is_listed = probe_impacted_by
+                    .iter()
+                    .any(|probe_dependency| probe_dependency.id == dependency.id);
+
+                if !is_listed {
+                    probe_impacted_by.push(dependency.clone());
+                }
+            }
+        }
+
+        probe.impacted_by = probe_impacted_by;
+    }
+}
+
+fn scan_and_bump_states(changes: &mut u32) -> Option<BumpedStates> {
     let mut bumped_replicas = Vec::new();
 
     let mut store = PROBER_STORE.write().unwrap();
@@ -88,11 +123,13 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
         debug!("aggregate probe: {}", probe_id);
 
         let mut probe_status = Status::Healthy;
+        let (mut probe_dead_nodes, mut probe_healthy_nodes) = (0, 0);
 
         for (node_id, node) in probe.nodes.iter_mut() {
             debug!("aggregate node: {}:{}", probe_id, node_id);
 
             let mut node_status = Status::Healthy;
+            let (mut node_dead_replicas, mut node_healthy_replicas) = (0, 0);
 
             for (replica_id, replica) in node.replicas.iter_mut() {
                 let mut replica_status = Status::Healthy;
@@ -182,6 +219,12 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
                     node_status = worst_status;
                 }
 
+                match replica_status {
+                    Status::Dead => node_dead_replicas += 1,
+                    Status::Healthy => node_healthy_replicas += 1,
+                    _ => {}
+                }
+
                 debug!(
                     "aggregated status for replica: {}:{}:{} => {:?}",
                     probe_id, node_id, replica_id, replica_status
@@ -192,22 +235,53 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
                     bumped_replicas.push(format!("{}:{}:{}", probe_id, node_id, replica_id));
                 }
 
+                if replica.status != replica_status {
+                    replica.changed = Some(SystemTime::now());
+                }
+
                 replica.status = replica_status;
             }
 
+            // Apply node aggregate policy (a dead node may only be sick, if enough replicas are up)
+            node_status = node.aggregate.resolve(
+                node_status,
+                node.replicas.len(),
+                node_dead_replicas,
+                node_healthy_replicas,
+            );
+
             // Bump probe status with worst node status?
             if let Some(worst_status) = check_child_status(&probe_status, &node_status) {
                 probe_status = worst_status;
             }
 
+            match node_status {
+                Status::Dead => probe_dead_nodes += 1,
+                Status::Healthy => probe_healthy_nodes += 1,
+                _ => {}
+            }
+
             debug!(
                 "aggregated status for node: {}:{} => {:?}",
                 probe_id, node_id, node_status
             );
 
+            if node.status != node_status {
+                node.changed = Some(SystemTime::now());
+            }
+
             node.status = node_status;
+            node.uptime.record(node_status);
         }
 
+        // Apply probe aggregate policy (a dead probe may only be sick, if enough nodes are up)
+        probe_status = probe.aggregate.resolve(
+            probe_status,
+            probe.nodes.len(),
+            probe_dead_nodes,
+            probe_healthy_nodes,
+        );
+
         // Bump general status with worst node status?
         if let Some(worst_status) = check_child_status(&general_status, &probe_status) {
             general_status = worst_status;
@@ -218,9 +292,40 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
             probe_id, probe_status
         );
 
+        // Count probe status changes (used to summarize digest notifications)
+        if probe.status != probe_status {
+            *changes += 1;
+
+            probe.changed = Some(SystemTime::now());
+        }
+
         probe.status = probe_status;
+        probe.uptime.record(probe_status);
     }
 
+    // Mark nodes impacted by upstream failures, and do not notify about their replicas \
+    //   (this avoids redundant alerts about dependents, and only points to the root cause)
+    mark_impacted(&mut store.states.probes);
+
+    // Summarize services statuses from their public view (their changes get recorded and \
+    //   published once the states store lock is released)
+    let public_summary = store.states.view(Visibility::Public, false).summarize();
+
+    bumped_replicas.retain(|replica_path| {
+        let mut replica_path_parts = replica_path.splitn(3, ':');
+
+        match (replica_path_parts.next(), replica_path_parts.next()) {
+            (Some(probe_id), Some(node_id)) => store
+                .states
+                .probes
+                .get(probe_id)
+                .and_then(|probe| probe.nodes.get(node_id))
+                .map(|node| node.impacted_by.is_empty())
+                .unwrap_or(true),
+            _ => true,
+        }
+    });
+
     // Check if general status has changed
     let has_changed = store.states.status != general_status;
 
@@ -335,7 +440,7 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
     store.states.status = general_status.to_owned();
     store.states.date = Some(time_now_as_string());
 
-    if should_notify == true {
+    let bumped_states = if should_notify {
         store.notified = Some(SystemTime::now());
 
         // Acquire escalated state (if non-zero)
@@ -355,10 +460,19 @@ fn scan_and_bump_states() -> Option<BumpedStates> {
         })
     } else {
         None
-    }
+    };
+
+    drop(store);
+
+    // Record and publish services status changes (outside of the lock, as this should not \
+    //   hold probe workers and responders)
+    dispatch_subscriber_transitions(&record_history(&public_summary));
+    publish_states_events(&public_summary);
+
+    bumped_states
 }
 
-fn time_now_as_string() -> String {
+pub fn time_now_as_string() -> String {
     time::OffsetDateTime::now_utc()
         .format(&TIME_NOW_FORMATTER)
         .unwrap_or("?".to_string())
@@ -369,82 +483,174 @@ fn dispatch_startup_notification() {
         if conf_notify.startup_notification == true {
             debug!("sending aggregate startup notification...");
 
-            notify(&BumpedStates {
-                status: Status::Healthy,
-                replicas: Vec::new(),
-                changed: true,
-                escalated: None,
-                startup: true,
-            });
+            notify(
+                &BumpedStates {
+                    status: Status::Healthy,
+                    replicas: Vec::new(),
+                    changed: true,
+                    escalated: None,
+                    startup: true,
+                },
+                None,
+            );
         }
     }
 }
 
-fn notify(bumped_states: &BumpedStates) {
-    let notification = Notification {
-        status: &bumped_states.status,
-        time: time_now_as_string(),
-        replicas: Vec::from_iter(bumped_states.replicas.iter().map(String::as_str)),
-        changed: bumped_states.changed,
-        escalated: bumped_states.escalated,
-        startup: bumped_states.startup,
-    };
+fn summarize_digest(changes: u32) -> (Status, Vec<String>, NotificationDigest) {
+    let store = PROBER_STORE.read().unwrap();
 
-    if let Some(ref notify) = APP_CONF.notify {
-        #[cfg(feature = "notifier-email")]
-        Notification::dispatch::<EmailNotifier>(notify, &notification).ok();
+    let mut replicas = Vec::new();
+    let mut digest = NotificationDigest {
+        changes,
+        dead: 0,
+        sick: 0,
+    };
 
-        #[cfg(feature = "notifier-twilio")]
-        Notification::dispatch::<TwilioNotifier>(notify, &notification).ok();
+    // Notice: the full list of failing replicas is collected upon sending the digest (not when \
+    //   the digest window opened), as more replicas may have died in the meantime.
+    for (probe_id, probe) in store.states.probes.iter() {
+        match probe.status {
+            Status::Dead => digest.dead += 1,
+            Status::Sick => digest.sick += 1,
+            Status::Healthy => {}
+        }
 
-        #[cfg(feature = "notifier-slack")]
-        Notification::dispatch::<SlackNotifier>(notify, &notification).ok();
+        for (node_id, node) in probe.nodes.iter() {
+            // Skip nodes impacted by an upstream failure (only list root causes)
+            if !node.impacted_by.is_empty() {
+                continue;
+            }
 
-        #[cfg(feature = "notifier-zulip")]
-        Notification::dispatch::<ZulipNotifier>(notify, &notification).ok();
+            for (replica_id, replica) in node.replicas.iter() {
+                if replica.status == Status::Dead {
+                    replicas.push(format!("{}:{}:{}", probe_id, node_id, replica_id));
+                }
+            }
+        }
+    }
 
-        #[cfg(feature = "notifier-telegram")]
-        Notification::dispatch::<TelegramNotifier>(notify, &notification).ok();
+    (store.states.status, replicas, digest)
+}
 
-        #[cfg(feature = "notifier-pushover")]
-        Notification::dispatch::<PushoverNotifier>(notify, &notification).ok();
+fn batch_digest(
+    pending_digest: &mut Option<PendingDigest>,
+    bumped_states: Option<&BumpedStates>,
+    changes: &mut u32,
+    digest_window: u64,
+) -> bool {
+    // Open a digest window, or merge into the pending digest window?
+    if let Some(bumped_states) = bumped_states {
+        if let Some(ref mut pending_digest_inner) = pending_digest {
+            pending_digest_inner.changed |= bumped_states.changed;
+            pending_digest_inner.escalated = bumped_states.escalated;
+        } else {
+            debug!("opened notification digest window ({}s)", digest_window);
 
-        #[cfg(feature = "notifier-gotify")]
-        Notification::dispatch::<GotifyNotifier>(notify, &notification).ok();
+            *pending_digest = Some(PendingDigest {
+                opened: SystemTime::now(),
+                changed: bumped_states.changed,
+                escalated: bumped_states.escalated,
+            });
+        }
+    }
 
-        #[cfg(feature = "notifier-xmpp")]
-        Notification::dispatch::<XMPPNotifier>(notify, &notification).ok();
+    match pending_digest {
+        Some(ref pending_digest_inner) => {
+            let digest_elapsed = SystemTime::now()
+                .duration_since(pending_digest_inner.opened)
+                .unwrap_or(Duration::from_secs(0));
+
+            // Digest window is over? Send a single notification for all grouped changes
+            if digest_elapsed >= Duration::from_secs(digest_window) {
+                let (status, replicas, digest) = summarize_digest(*changes);
+
+                info!("closing notification digest window ({})", digest.summary());
+
+                notify(
+                    &BumpedStates {
+                        status,
+                        replicas,
+                        changed: pending_digest_inner.changed,
+                        escalated: pending_digest_inner.escalated,
+                        startup: false,
+                    },
+                    Some(digest),
+                );
 
-        #[cfg(feature = "notifier-matrix")]
-        Notification::dispatch::<MatrixNotifier>(notify, &notification).ok();
+                *pending_digest = None;
+                *changes = 0;
 
-        #[cfg(feature = "notifier-webex")]
-        Notification::dispatch::<WebExNotifier>(notify, &notification).ok();
+                true
+            } else {
+                false
+            }
+        }
+        None => {
+            // No digest window is open, forget about changes that did not trigger any \
+            //   notification
+            *changes = 0;
 
-        #[cfg(feature = "notifier-webhook")]
-        Notification::dispatch::<WebHookNotifier>(notify, &notification).ok();
+            false
+        }
     }
 }
 
+fn notify(bumped_states: &BumpedStates, digest: Option<NotificationDigest>) {
+    // Notice: this enqueues the notification to all notifiers, which then get dispatched \
+    //   asynchronously by notifier workers; so that the aggregate loop never blocks on slow \
+    //   notifier providers.
+    dispatch_notification(DispatchJob {
+        status: bumped_states.status,
+        time: time_now_as_string(),
+        replicas: bumped_states.replicas.to_owned(),
+        changed: bumped_states.changed,
+        escalated: bumped_states.escalated,
+        startup: bumped_states.startup,
+        digest,
+        test: false,
+    });
+}
+
 pub fn run() {
     // Notify that systems are healthy (when booting up aggregator)
     dispatch_startup_notification();
 
+    let digest_window = APP_CONF
+        .notify
+        .as_ref()
+        .map(|notify| notify.digest_window)
+        .unwrap_or(0);
+
+    let mut pending_digest = None;
+    let mut digest_changes = 0;
+
     // Start aggregate loop
     loop {
         debug!("running an aggregate operation...");
 
         // Should notify after bump?
-        let bumped_states = scan_and_bump_states();
-
-        if let Some(ref bumped_states_inner) = bumped_states {
-            notify(bumped_states_inner);
-        }
+        let bumped_states = scan_and_bump_states(&mut digest_changes);
+
+        // Notice: if a digest window is set, notifications are batched in the digest window \
+        //   and sent as a single digest notification once the window is over; this avoids \
+        //   alert storms when a shared dependency fails and multiple services die in a row.
+        let notified = if digest_window > 0 {
+            batch_digest(
+                &mut pending_digest,
+                bumped_states.as_ref(),
+                &mut digest_changes,
+                digest_window,
+            )
+        } else {
+            if let Some(ref bumped_states_inner) = bumped_states {
+                notify(bumped_states_inner, None);
+            }
 
-        info!(
-            "ran aggregate operation (notified: {})",
             bumped_states.is_some()
-        );
+        };
+
+        info!("ran aggregate operation (notified: {})", notified);
 
         // Hold for next aggregate run
         thread::sleep(Duration::from_secs(AGGREGATE_INTERVAL_SECONDS));
@@ -7,11 +7,17 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
+use chrono::{NaiveDateTime, NaiveTime};
+use chrono_tz::Tz;
+use serde_json_path::JsonPath;
 use url_serde::SerdeUrl;
 
 use super::defaults;
 use super::regex::Regex;
+use crate::prober::aggregate::Aggregate;
 use crate::prober::mode::Mode;
+use crate::prober::status::Status;
+use crate::prober::visibility::Visibility;
 
 #[derive(Deserialize)]
 pub struct Config {
@@ -21,6 +27,7 @@ pub struct Config {
     pub metrics: ConfigMetrics,
     pub plugins: Option<ConfigPlugins>,
     pub notify: Option<ConfigNotify>,
+    pub subscribers: Option<ConfigSubscribers>,
     pub probe: ConfigProbe,
 }
 
@@ -40,6 +47,7 @@ pub struct ConfigServer {
 
     pub manager_token: String,
     pub reporter_token: String,
+    pub viewer_token: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -107,6 +115,9 @@ pub struct ConfigMetrics {
 
     #[serde(default = "defaults::metrics_local_delay_dead")]
     pub local_delay_dead: u64,
+
+    #[serde(default = "defaults::metrics_discover_interval")]
+    pub discover_interval: u64,
 }
 
 #[derive(Deserialize)]
@@ -125,6 +136,24 @@ pub struct ConfigNotify {
     #[serde(default = "defaults::notify_reminder_escalate")]
     pub reminder_escalate: bool,
 
+    #[serde(default = "defaults::notify_dispatch_queue_size")]
+    pub dispatch_queue_size: usize,
+
+    #[serde(default = "defaults::notify_dispatch_workers")]
+    pub dispatch_workers: u16,
+
+    #[serde(default = "defaults::notify_dispatch_timeout")]
+    pub dispatch_timeout: u64,
+
+    #[serde(default = "defaults::notify_digest_window")]
+    pub digest_window: u64,
+
+    #[serde(default)]
+    pub dispatch: Vec<ConfigNotifyDispatch>,
+
+    #[serde(default)]
+    pub schedule: Vec<ConfigNotifySchedule>,
+
     #[cfg(feature = "notifier-email")]
     pub email: Option<ConfigNotifyEmail>,
 
@@ -159,6 +188,30 @@ pub struct ConfigNotify {
     pub webhook: Option<ConfigNotifyWebHook>,
 }
 
+#[derive(Deserialize)]
+pub struct ConfigNotifyDispatch {
+    pub notifiers: Vec<String>,
+    pub queue_size: Option<usize>,
+    pub workers: Option<u16>,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ConfigNotifySchedule {
+    pub notifiers: Vec<String>,
+
+    #[serde(default = "defaults::notify_schedule_timezone")]
+    pub timezone: Tz,
+
+    pub rotation_start: Option<NaiveDateTime>,
+
+    #[serde(default = "defaults::notify_schedule_rotation_days")]
+    pub rotation_days: u16,
+
+    pub quiet_hours_from: Option<NaiveTime>,
+    pub quiet_hours_to: Option<NaiveTime>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum ConfigNotifyReminderBackoffFunction {
     #[serde(rename = "none")]
@@ -195,8 +248,10 @@ pub struct ConfigPluginsRabbitMQ {
 #[cfg(feature = "notifier-email")]
 #[derive(Deserialize)]
 pub struct ConfigNotifyEmail {
-    pub to: String,
+    pub to: ConfigNotifyEmailTo,
     pub from: String,
+    pub reply_to: Option<String>,
+    pub list_id: Option<String>,
 
     #[serde(default = "defaults::notify_email_smtp_host")]
     pub smtp_host: String,
@@ -207,6 +262,8 @@ pub struct ConfigNotifyEmail {
     pub smtp_username: Option<String>,
     pub smtp_password: Option<String>,
 
+    pub smtp_tls: Option<ConfigNotifyEmailSMTPTLS>,
+
     #[serde(default = "defaults::notify_email_smtp_encrypt")]
     pub smtp_encrypt: bool,
 
@@ -214,6 +271,30 @@ pub struct ConfigNotifyEmail {
     pub reminders_only: bool,
 }
 
+#[cfg(feature = "notifier-email")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ConfigNotifyEmailTo {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[cfg(feature = "notifier-email")]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ConfigNotifyEmailSMTPTLS {
+    #[serde(rename = "implicit")]
+    Implicit,
+
+    #[serde(rename = "starttls")]
+    StartTLS,
+
+    #[serde(rename = "opportunistic")]
+    Opportunistic,
+
+    #[serde(rename = "none")]
+    None,
+}
+
 #[cfg(feature = "notifier-twilio")]
 #[derive(Deserialize)]
 pub struct ConfigNotifyTwilio {
@@ -320,6 +401,21 @@ pub struct ConfigNotifyWebHook {
     pub hook_url: SerdeUrl,
 }
 
+#[derive(Deserialize)]
+pub struct ConfigSubscribers {
+    #[serde(default = "defaults::subscribers_store_path")]
+    pub store_path: PathBuf,
+
+    #[serde(default = "defaults::subscribers_email")]
+    pub email: bool,
+
+    #[serde(default = "defaults::subscribers_webhook")]
+    pub webhook: bool,
+
+    #[serde(default = "defaults::subscribers_maximum")]
+    pub maximum: usize,
+}
+
 #[derive(Deserialize)]
 pub struct ConfigProbe {
     pub service: Vec<ConfigProbeService>,
@@ -329,6 +425,19 @@ pub struct ConfigProbe {
 pub struct ConfigProbeService {
     pub id: String,
     pub label: String,
+
+    #[serde(default = "defaults::probe_service_visibility")]
+    pub visibility: Visibility,
+
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    #[serde(default = "defaults::probe_service_aggregate")]
+    pub aggregate: Aggregate,
+
+    pub aggregate_quorum: Option<u8>,
+    pub aggregate_minimum: Option<u32>,
+
     pub node: Vec<ConfigProbeServiceNode>,
 }
 
@@ -337,9 +446,27 @@ pub struct ConfigProbeServiceNode {
     pub id: String,
     pub label: String,
     pub mode: Mode,
+
+    #[serde(default = "defaults::probe_service_visibility")]
+    pub visibility: Visibility,
+
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    #[serde(default = "defaults::probe_service_aggregate")]
+    pub aggregate: Aggregate,
+
+    pub aggregate_quorum: Option<u8>,
+    pub aggregate_minimum: Option<u32>,
+
     pub replicas: Option<Vec<String>>,
     pub scripts: Option<Vec<String>>,
 
+    pub discover_dns_srv: Option<String>,
+    pub discover_dns_host: Option<String>,
+    pub discover_file: Option<PathBuf>,
+    pub discover_template: Option<String>,
+
     #[serde(default)]
     #[serde(with = "http_serde::header_map")]
     pub http_headers: http::HeaderMap,
@@ -348,6 +475,50 @@ pub struct ConfigProbeServiceNode {
     pub http_body: Option<String>,
     pub http_body_healthy_match: Option<Regex>,
 
+    #[serde(default)]
+    pub http_assert: Vec<ConfigProbeServiceNodeHTTPAssert>,
+
+    #[serde(default)]
+    pub http_steps: Vec<ConfigProbeServiceNodeHTTPStep>,
+
+    #[serde(default)]
+    pub http_redirects: usize,
+
+    pub http_tls_ca: Option<PathBuf>,
+    pub http_tls_cert: Option<PathBuf>,
+    pub http_tls_key: Option<PathBuf>,
+
+    #[serde(default = "defaults::probe_service_node_http_tls_insecure")]
+    pub http_tls_insecure: bool,
+
+    pub http_tls_sni: Option<String>,
+    pub http_ip_version: Option<ConfigProbeServiceNodeHTTPIPVersion>,
+
+    pub websocket_message: Option<String>,
+    pub websocket_reply_healthy_match: Option<Regex>,
+
+    pub udp_payload: Option<String>,
+
+    #[serde(default = "defaults::probe_service_node_udp_payload_encoding")]
+    pub udp_payload_encoding: ConfigProbeServiceNodePayloadEncoding,
+
+    pub udp_reply_healthy_match: Option<Regex>,
+
+    pub tcp_banner_healthy_match: Option<Regex>,
+    pub tcp_payload: Option<String>,
+
+    #[serde(default = "defaults::probe_service_node_tcp_payload_encoding")]
+    pub tcp_payload_encoding: ConfigProbeServiceNodePayloadEncoding,
+
+    pub tcp_reply_healthy_match: Option<Regex>,
+    pub tcp_tls: Option<ConfigProbeServiceNodeTCPTLS>,
+
+    #[serde(default)]
+    pub tcp_starttls: Vec<ConfigProbeServiceNodeTCPStartTLSStep>,
+
+    #[serde(default = "defaults::probe_service_node_tcp_tls_insecure")]
+    pub tcp_tls_insecure: bool,
+
     #[serde(default = "defaults::probe_service_node_reveal_replica_name")]
     pub reveal_replica_name: bool,
 
@@ -359,6 +530,85 @@ pub struct ConfigProbeServiceNode {
     pub rabbitmq_queue_nack_dead_above: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigProbeServiceNodeHTTPAssert {
+    pub status: Option<Vec<u16>>,
+
+    pub header: Option<String>,
+    pub header_match: Option<Regex>,
+
+    pub json_path: Option<JsonPath>,
+    pub json_match: Option<Regex>,
+    pub json_equals: Option<serde_json::Value>,
+
+    pub body_size_above: Option<usize>,
+    pub body_size_below: Option<usize>,
+
+    pub latency_below: Option<u64>,
+
+    #[serde(default = "defaults::probe_service_node_http_assert_failure")]
+    pub failure: Status,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigProbeServiceNodeHTTPStep {
+    pub url: String,
+    pub method: Option<ConfigProbeServiceNodeHTTPMethod>,
+
+    #[serde(default)]
+    #[serde(with = "http_serde::header_map")]
+    pub headers: http::HeaderMap,
+
+    pub body: Option<String>,
+
+    #[serde(default)]
+    pub extract: Vec<ConfigProbeServiceNodeHTTPStepExtract>,
+
+    #[serde(default)]
+    pub assert: Vec<ConfigProbeServiceNodeHTTPAssert>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigProbeServiceNodeHTTPStepExtract {
+    pub name: String,
+    pub json_path: Option<JsonPath>,
+    pub header: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ConfigProbeServiceNodeTCPTLS {
+    #[serde(rename = "tls")]
+    TLS,
+
+    #[serde(rename = "starttls")]
+    StartTLS,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigProbeServiceNodeTCPStartTLSStep {
+    pub send: String,
+    pub expect: Regex,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ConfigProbeServiceNodePayloadEncoding {
+    #[serde(rename = "text")]
+    Text,
+
+    #[serde(rename = "hex")]
+    Hex,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ConfigProbeServiceNodeHTTPIPVersion {
+    #[serde(rename = "ipv4")]
+    IPv4,
+
+    #[serde(rename = "ipv6")]
+    IPv6,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ConfigProbeServiceNodeHTTPMethod {
     #[serde(rename = "HEAD")]
@@ -7,7 +7,12 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use super::config::ConfigNotifyReminderBackoffFunction;
+use chrono_tz::Tz;
+
+use super::config::{ConfigNotifyReminderBackoffFunction, ConfigProbeServiceNodePayloadEncoding};
+use crate::prober::aggregate::Aggregate;
+use crate::prober::status::Status;
+use crate::prober::visibility::Visibility;
 
 pub fn server_log_level() -> String {
     "error".to_string()
@@ -89,6 +94,10 @@ pub fn metrics_local_delay_dead() -> u64 {
     40
 }
 
+pub fn metrics_discover_interval() -> u64 {
+    60
+}
+
 pub fn notify_startup_notification() -> bool {
     true
 }
@@ -105,6 +114,30 @@ pub fn notify_reminder_escalate() -> bool {
     false
 }
 
+pub fn notify_dispatch_queue_size() -> usize {
+    64
+}
+
+pub fn notify_dispatch_workers() -> u16 {
+    1
+}
+
+pub fn notify_dispatch_timeout() -> u64 {
+    30
+}
+
+pub fn notify_digest_window() -> u64 {
+    0
+}
+
+pub fn notify_schedule_timezone() -> Tz {
+    Tz::UTC
+}
+
+pub fn notify_schedule_rotation_days() -> u16 {
+    7
+}
+
 #[cfg(feature = "notifier-email")]
 pub fn notify_email_smtp_host() -> String {
     "localhost".to_string()
@@ -129,6 +162,50 @@ pub fn notify_generic_reminders_only() -> bool {
     false
 }
 
+pub fn subscribers_store_path() -> PathBuf {
+    PathBuf::from("./subscribers.json")
+}
+
+pub fn subscribers_email() -> bool {
+    true
+}
+
+pub fn subscribers_webhook() -> bool {
+    false
+}
+
+pub fn subscribers_maximum() -> usize {
+    10000
+}
+
+pub fn probe_service_visibility() -> Visibility {
+    Visibility::Public
+}
+
+pub fn probe_service_aggregate() -> Aggregate {
+    Aggregate::Worst
+}
+
 pub fn probe_service_node_reveal_replica_name() -> bool {
     false
 }
+
+pub fn probe_service_node_http_tls_insecure() -> bool {
+    false
+}
+
+pub fn probe_service_node_udp_payload_encoding() -> ConfigProbeServiceNodePayloadEncoding {
+    ConfigProbeServiceNodePayloadEncoding::Text
+}
+
+pub fn probe_service_node_tcp_payload_encoding() -> ConfigProbeServiceNodePayloadEncoding {
+    ConfigProbeServiceNodePayloadEncoding::Text
+}
+
+pub fn probe_service_node_tcp_tls_insecure() -> bool {
+    false
+}
+
+pub fn probe_service_node_http_assert_failure() -> Status {
+    Status::Dead
+}
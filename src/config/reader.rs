@@ -13,6 +13,10 @@ use std::{
 use toml;
 
 use super::config::*;
+use crate::notifier::dispatcher::notifier_names;
+use crate::prober::dependency::{
+    validate as validate_dependency_graph, DependencyError, DependencyNode, DependencyService,
+};
 use crate::APP_ARGS;
 
 pub struct ConfigReader;
@@ -42,7 +46,19 @@ impl ConfigReader {
 
     fn validate(config: &Config) {
         // Validate all identifiers
-        Self::validate_identifiers(config)
+        Self::validate_identifiers(config);
+
+        // Validate all dependencies
+        Self::validate_dependencies(config);
+
+        // Validate all notifier dispatch options
+        Self::validate_dispatch(config);
+
+        // Validate all notifier schedules
+        Self::validate_schedules(config);
+
+        // Validate subscribers
+        Self::validate_subscribers(config)
     }
 
     fn validate_identifiers(config: &Config) {
@@ -72,4 +88,150 @@ impl ConfigReader {
             }
         }
     }
+
+    fn validate_dependencies(config: &Config) {
+        let services: Vec<DependencyService> = config
+            .probe
+            .service
+            .iter()
+            .map(|service| DependencyService {
+                id: service.id.as_str(),
+                depends_on: service.depends_on.iter().map(String::as_str).collect(),
+                nodes: service
+                    .node
+                    .iter()
+                    .map(|node| DependencyNode {
+                        id: node.id.as_str(),
+                        depends_on: node.depends_on.iter().map(String::as_str).collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        match validate_dependency_graph(&services) {
+            Ok(_) => {}
+            Err(DependencyError::Unknown(reference, service_id)) => panic!(
+                "configuration has unknown dependency: {} in service: {}",
+                reference, service_id
+            ),
+            Err(DependencyError::Cycle(service_id, node_id)) => panic!(
+                "configuration has dependency cycle on node: {}:{}",
+                service_id, node_id
+            ),
+        }
+    }
+
+    fn validate_dispatch(config: &Config) {
+        if let Some(ref notify) = config.notify {
+            // Notice: a zero queue size would drop notifications whenever no worker is idle, \
+            //   while zero workers or a zero timeout would never deliver any notification.
+            if notify.dispatch_queue_size == 0
+                || notify.dispatch_workers == 0
+                || notify.dispatch_timeout == 0
+            {
+                panic!(
+                    "configuration has zero dispatch options (dispatch_queue_size, \
+                        dispatch_workers and dispatch_timeout must be above zero)"
+                )
+            }
+
+            // Scan for notifiers that belong to multiple dispatch options
+            let mut dispatch_notifiers = HashSet::new();
+
+            for dispatch in notify.dispatch.iter() {
+                for notifier in dispatch.notifiers.iter() {
+                    Self::validate_notifier_name(notifier);
+
+                    // Notifier was already previously inserted? (caught a duplicate)
+                    if !dispatch_notifiers.insert(notifier) {
+                        panic!(
+                            "configuration has notifier in multiple dispatch options: {}",
+                            notifier
+                        )
+                    }
+                }
+
+                if dispatch.queue_size == Some(0)
+                    || dispatch.workers == Some(0)
+                    || dispatch.timeout == Some(0)
+                {
+                    panic!(
+                        "configuration has zero dispatch options (queue_size, workers and \
+                            timeout must be above zero)"
+                    )
+                }
+            }
+        }
+    }
+
+    fn validate_notifier_name(notifier: &str) {
+        // Notifier is unknown? (or is not built-in)
+        if !notifier_names().contains(&notifier) {
+            panic!("configuration has unknown notifier: {}", notifier)
+        }
+    }
+
+    fn validate_schedules(config: &Config) {
+        if let Some(ref notify) = config.notify {
+            // Scan for notifiers that belong to multiple schedules
+            let mut schedule_notifiers = HashSet::new();
+
+            for schedule in notify.schedule.iter() {
+                for notifier in schedule.notifiers.iter() {
+                    Self::validate_notifier_name(notifier);
+
+                    // Notifier was already previously inserted? (caught a duplicate)
+                    if !schedule_notifiers.insert(notifier) {
+                        panic!(
+                            "configuration has notifier in multiple schedules: {}",
+                            notifier
+                        )
+                    }
+                }
+
+                if schedule.rotation_days == 0 {
+                    panic!("configuration has schedule with a zero rotation_days")
+                }
+
+                if schedule.quiet_hours_from.is_some() != schedule.quiet_hours_to.is_some() {
+                    panic!(
+                        "configuration has schedule with incomplete quiet hours (both \
+                            quiet_hours_from and quiet_hours_to must be set)"
+                    )
+                }
+            }
+        }
+    }
+
+    fn validate_subscribers(config: &Config) {
+        if let Some(ref subscribers) = config.subscribers {
+            // Email subscriptions are delivered with the email notifier transport
+            if subscribers.email
+                && !config
+                    .notify
+                    .as_ref()
+                    .map(Self::has_email_transport)
+                    .unwrap_or(false)
+            {
+                panic!(
+                    "configuration has email subscribers enabled, but no email notifier is \
+                        configured (notify.email is required to deliver emails)"
+                )
+            }
+
+            if !subscribers.email && !subscribers.webhook {
+                panic!("configuration has subscribers enabled, but no subscription channel")
+            }
+        }
+    }
+
+    #[cfg(feature = "notifier-email")]
+    fn has_email_transport(notify: &ConfigNotify) -> bool {
+        notify.email.is_some()
+    }
+
+    #[cfg(not(feature = "notifier-email"))]
+    fn has_email_transport(_: &ConfigNotify) -> bool {
+        false
+    }
 }
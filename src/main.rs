@@ -16,6 +16,7 @@ mod config;
 mod notifier;
 mod prober;
 mod responder;
+mod subscriber;
 
 use std::ops::Deref;
 use std::str::FromStr;
@@ -29,20 +30,26 @@ use crate::aggregator::manager::run as run_aggregator;
 use crate::config::config::Config;
 use crate::config::logger::ConfigLogger;
 use crate::config::reader::ConfigReader;
+use crate::prober::discover::run as run_discover_prober;
 use crate::prober::manager::{
     initialize_store as initialize_store_prober, run_poll as run_poll_prober,
     run_script as run_script_prober,
 };
 use crate::responder::manager::run as run_responder;
+use crate::subscriber::store::load as load_store_subscriber;
 
 struct AppArgs {
     config: String,
 }
 
-pub static THREAD_NAME_PROBER_POLL: &'static str = "vigil-prober-poll";
-pub static THREAD_NAME_PROBER_SCRIPT: &'static str = "vigil-prober-script";
-pub static THREAD_NAME_AGGREGATOR: &'static str = "vigil-aggregator";
-pub static THREAD_NAME_RESPONDER: &'static str = "vigil-responder";
+pub static THREAD_NAME_PROBER_POLL: &str = "vigil-prober-poll";
+pub static THREAD_NAME_PROBER_SCRIPT: &str = "vigil-prober-script";
+pub static THREAD_NAME_PROBER_DISCOVER: &str = "vigil-prober-discover";
+pub static THREAD_NAME_PROBER_GRPC: &str = "vigil-prober-grpc";
+pub static THREAD_NAME_AGGREGATOR: &str = "vigil-aggregator";
+pub static THREAD_NAME_NOTIFIER_DISPATCH: &str = "vigil-notifier-dispatch";
+pub static THREAD_NAME_SUBSCRIBER_DISPATCH: &str = "vigil-subscriber-dispatch";
+pub static THREAD_NAME_RESPONDER: &str = "vigil-responder";
 
 macro_rules! gen_spawn_managed {
     ($name:expr, $method:ident, $thread_name:ident, $managed_fn:ident) => {
@@ -90,6 +97,12 @@ gen_spawn_managed!(
     THREAD_NAME_PROBER_SCRIPT,
     run_script_prober
 );
+gen_spawn_managed!(
+    "prober-discover",
+    spawn_discover_prober,
+    THREAD_NAME_PROBER_DISCOVER,
+    run_discover_prober
+);
 gen_spawn_managed!(
     "aggregator",
     spawn_aggregator,
@@ -159,9 +172,13 @@ fn main() {
     // Initialize prober store
     initialize_store_prober();
 
+    // Load subscribers store (if subscribers are enabled)
+    load_store_subscriber();
+
     // Spawn probes (background thread)
     thread::spawn(spawn_poll_prober);
     thread::spawn(spawn_script_prober);
+    thread::spawn(spawn_discover_prober);
 
     // Spawn aggregator (background thread)
     thread::spawn(spawn_aggregator);
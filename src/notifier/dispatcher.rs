@@ -0,0 +1,453 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::VecDeque;
+use std::iter::FromIterator;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::generic::{DispatchOutcome, GenericNotifier, Notification, NotificationDigest};
+use super::schedule::resolve as resolve_schedule;
+use crate::aggregator::manager::time_now_as_string;
+use crate::config::config::ConfigNotify;
+use crate::prober::status::Status;
+use crate::APP_CONF;
+use crate::THREAD_NAME_NOTIFIER_DISPATCH;
+
+#[cfg(feature = "notifier-email")]
+use super::email::EmailNotifier;
+
+#[cfg(feature = "notifier-twilio")]
+use super::twilio::TwilioNotifier;
+
+#[cfg(feature = "notifier-slack")]
+use super::slack::SlackNotifier;
+
+#[cfg(feature = "notifier-zulip")]
+use super::zulip::ZulipNotifier;
+
+#[cfg(feature = "notifier-telegram")]
+use super::telegram::TelegramNotifier;
+
+#[cfg(feature = "notifier-pushover")]
+use super::pushover::PushoverNotifier;
+
+#[cfg(feature = "notifier-gotify")]
+use super::gotify::GotifyNotifier;
+
+#[cfg(feature = "notifier-xmpp")]
+use super::xmpp::XMPPNotifier;
+
+#[cfg(feature = "notifier-matrix")]
+use super::matrix::MatrixNotifier;
+
+#[cfg(feature = "notifier-webex")]
+use super::webex::WebExNotifier;
+
+#[cfg(feature = "notifier-webhook")]
+use super::webhook::WebHookNotifier;
+
+const DISPATCH_LOG_MAXIMUM_ENTRIES: usize = 1000;
+
+lazy_static! {
+    pub static ref STORE: Arc<RwLock<Store>> = Arc::new(RwLock::new(Store {
+        log: VecDeque::with_capacity(DISPATCH_LOG_MAXIMUM_ENTRIES),
+    }));
+    static ref QUEUES: Vec<DispatchQueue> = make_queues();
+}
+
+pub struct Store {
+    pub log: VecDeque<DispatchLogEntry>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DispatchLogEntry {
+    pub notifier: &'static str,
+    pub time: u64,
+    pub status: Status,
+    pub replicas: Vec<String>,
+    pub attempts: u8,
+    pub outcome: DispatchOutcome,
+    pub reason: Option<String>,
+    pub test: bool,
+}
+
+pub struct DispatchJob {
+    pub status: Status,
+    pub time: String,
+    pub replicas: Vec<String>,
+    pub changed: bool,
+    pub escalated: Option<u16>,
+    pub startup: bool,
+    pub digest: Option<NotificationDigest>,
+    pub test: bool,
+}
+
+#[derive(Serialize)]
+pub struct DispatchTestResult {
+    pub notifier: &'static str,
+    pub attempts: u8,
+    pub outcome: DispatchOutcome,
+    pub reason: Option<String>,
+}
+
+struct DispatchQueue {
+    name: &'static str,
+    sender: SyncSender<Arc<DispatchJob>>,
+}
+
+type DispatchFn =
+    fn(&ConfigNotify, &Notification, Duration) -> (DispatchOutcome, u8, Option<String>);
+
+#[derive(Clone, Copy)]
+struct DispatchNotifier {
+    name: &'static str,
+    dispatch: DispatchFn,
+}
+
+struct DispatchOptions {
+    queue_size: usize,
+    workers: u16,
+    timeout: Duration,
+}
+
+impl DispatchNotifier {
+    fn of<N: GenericNotifier>() -> Self {
+        DispatchNotifier {
+            name: N::name(),
+            dispatch: Notification::dispatch::<N>,
+        }
+    }
+}
+
+impl DispatchJob {
+    fn notification(&self, on_call: usize) -> Notification<'_> {
+        Notification {
+            status: &self.status,
+            time: self.time.to_owned(),
+            replicas: Vec::from_iter(self.replicas.iter().map(String::as_str)),
+            changed: self.changed,
+            escalated: self.escalated,
+            startup: self.startup,
+            on_call,
+            digest: self.digest,
+        }
+    }
+}
+
+fn list_notifiers() -> Vec<DispatchNotifier> {
+    vec![
+        #[cfg(feature = "notifier-email")]
+        DispatchNotifier::of::<EmailNotifier>(),
+        #[cfg(feature = "notifier-twilio")]
+        DispatchNotifier::of::<TwilioNotifier>(),
+        #[cfg(feature = "notifier-slack")]
+        DispatchNotifier::of::<SlackNotifier>(),
+        #[cfg(feature = "notifier-zulip")]
+        DispatchNotifier::of::<ZulipNotifier>(),
+        #[cfg(feature = "notifier-telegram")]
+        DispatchNotifier::of::<TelegramNotifier>(),
+        #[cfg(feature = "notifier-pushover")]
+        DispatchNotifier::of::<PushoverNotifier>(),
+        #[cfg(feature = "notifier-gotify")]
+        DispatchNotifier::of::<GotifyNotifier>(),
+        #[cfg(feature = "notifier-xmpp")]
+        DispatchNotifier::of::<XMPPNotifier>(),
+        #[cfg(feature = "notifier-matrix")]
+        DispatchNotifier::of::<MatrixNotifier>(),
+        #[cfg(feature = "notifier-webex")]
+        DispatchNotifier::of::<WebExNotifier>(),
+        #[cfg(feature = "notifier-webhook")]
+        DispatchNotifier::of::<WebHookNotifier>(),
+    ]
+}
+
+pub fn notifier_names() -> Vec<&'static str> {
+    list_notifiers()
+        .into_iter()
+        .map(|notifier| notifier.name)
+        .collect()
+}
+
+fn resolve_options(name: &str) -> DispatchOptions {
+    if let Some(ref notify) = APP_CONF.notify {
+        // Apply per-notifier overrides over global dispatch options (if any)
+        let overrides = notify.dispatch.iter().find(|dispatch| {
+            dispatch
+                .notifiers
+                .iter()
+                .any(|dispatch_notifier| dispatch_notifier == name)
+        });
+
+        DispatchOptions {
+            queue_size: overrides
+                .and_then(|overrides| overrides.queue_size)
+                .unwrap_or(notify.dispatch_queue_size),
+            workers: overrides
+                .and_then(|overrides| overrides.workers)
+                .unwrap_or(notify.dispatch_workers),
+            timeout: Duration::from_secs(
+                overrides
+                    .and_then(|overrides| overrides.timeout)
+                    .unwrap_or(notify.dispatch_timeout),
+            ),
+        }
+    } else {
+        DispatchOptions {
+            queue_size: 0,
+            workers: 0,
+            timeout: Duration::from_secs(0),
+        }
+    }
+}
+
+fn make_queues() -> Vec<DispatchQueue> {
+    list_notifiers().into_iter().map(make_queue).collect()
+}
+
+fn make_queue(notifier: DispatchNotifier) -> DispatchQueue {
+    let options = resolve_options(notifier.name);
+
+    let (sender, receiver) = mpsc::sync_channel(options.queue_size);
+
+    // Notice: all workers for a given notifier share the same receiver, which lets them pull \
+    //   jobs from the queue as soon as they are done with their previous job. Jobs for a given \
+    //   notifier are only delivered in order if a single worker is configured.
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    for worker_index in 0..options.workers {
+        let worker_receiver = receiver.clone();
+
+        let worker = thread::Builder::new()
+            .name(format!(
+                "{}-{}-{}",
+                THREAD_NAME_NOTIFIER_DISPATCH, notifier.name, worker_index
+            ))
+            .spawn(move || run_worker(notifier, worker_receiver));
+
+        if let Err(err) = worker {
+            error!(
+                "could not spawn dispatch worker for provider: {} (error: {})",
+                notifier.name, err
+            );
+        }
+    }
+
+    debug!(
+        "started dispatch queue for provider: {} (size: {}, workers: {})",
+        notifier.name, options.queue_size, options.workers
+    );
+
+    DispatchQueue {
+        name: notifier.name,
+        sender,
+    }
+}
+
+fn run_worker(notifier: DispatchNotifier, receiver: Arc<Mutex<Receiver<Arc<DispatchJob>>>>) {
+    loop {
+        // Wait for next job (the lock is released as soon as a job is received)
+        let job = {
+            match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => break,
+            }
+        };
+
+        match job {
+            Ok(job) => {
+                if let Some(ref notify) = APP_CONF.notify {
+                    // Notice: the notifier schedule is resolved upon dispatch (not enqueue), as \
+                    //   the notification may have been waiting in queue across a shift change.
+                    let schedule = resolve_schedule(notifier.name);
+
+                    if schedule.muted {
+                        info!(
+                            "muted notification for provider: {} (quiet hours)",
+                            notifier.name
+                        );
+
+                        record_outcome(notifier.name, &job, DispatchOutcome::Muted, 0, None);
+                    } else {
+                        let (outcome, attempts, reason) =
+                            dispatch_notifier(notifier, notify, &job, schedule.on_call);
+
+                        record_outcome(notifier.name, &job, outcome, attempts, reason);
+                    }
+                }
+            }
+            Err(_) => break,
+        }
+    }
+
+    warn!("dispatch worker stopped for provider: {}", notifier.name);
+}
+
+fn dispatch_notifier(
+    notifier: DispatchNotifier,
+    notify: &ConfigNotify,
+    job: &DispatchJob,
+    on_call: usize,
+) -> (DispatchOutcome, u8, Option<String>) {
+    // Notice: the dispatch runs in the calling worker, as each attempt is bounded by the \
+    //   notifier transport timeouts (which are given the time left before the deadline).
+    (notifier.dispatch)(
+        notify,
+        &job.notification(on_call),
+        resolve_options(notifier.name).timeout,
+    )
+}
+
+fn record_outcome(
+    name: &'static str,
+    job: &DispatchJob,
+    outcome: DispatchOutcome,
+    attempts: u8,
+    reason: Option<String>,
+) {
+    // Notice: skipped notifications are not recorded, as nothing was attempted
+    if outcome != DispatchOutcome::Skipped {
+        let mut store = STORE.write().unwrap();
+
+        // Evict oldest log entry? (the log is a bounded ring buffer)
+        if store.log.len() >= DISPATCH_LOG_MAXIMUM_ENTRIES {
+            store.log.pop_front();
+        }
+
+        store.log.push_back(DispatchLogEntry {
+            notifier: name,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            status: job.status,
+            replicas: job.replicas.to_owned(),
+            attempts,
+            outcome,
+            reason: reason.or_else(|| outcome_reason(outcome).map(str::to_string)),
+            test: job.test,
+        });
+    }
+}
+
+fn outcome_reason(outcome: DispatchOutcome) -> Option<&'static str> {
+    match outcome {
+        DispatchOutcome::Failed => Some("all attempts failed"),
+        DispatchOutcome::TimedOut => Some("dispatch timeout exceeded"),
+        DispatchOutcome::Skipped => Some("notifier is not configured"),
+        DispatchOutcome::Muted => Some("quiet hours are active"),
+        _ => None,
+    }
+}
+
+pub fn dispatch_test(name: Option<&str>) -> Option<Vec<DispatchTestResult>> {
+    let notifiers: Vec<DispatchNotifier> = list_notifiers()
+        .into_iter()
+        .filter(|notifier| name.map(|name| name == notifier.name).unwrap_or(true))
+        .collect();
+
+    // Target notifier does not exist? (or is not built-in)
+    if notifiers.is_empty() && name.is_some() {
+        return None;
+    }
+
+    let mut results = Vec::new();
+
+    if let Some(ref notify) = APP_CONF.notify {
+        // Build a synthetic notification (it gets dispatched as a startup notification, which \
+        //   is both harmless and accepted by notifiers that are set to only send reminders)
+        let job = DispatchJob {
+            status: Status::Healthy,
+            time: time_now_as_string(),
+            replicas: Vec::new(),
+            changed: false,
+            escalated: None,
+            startup: true,
+            digest: None,
+            test: true,
+        };
+
+        // Dispatch synchronously to notifiers (bypassing dispatch queues, as the caller \
+        //   expects the dispatch results; quiet hours are ignored, as the test is explicit)
+        for notifier in notifiers {
+            info!(
+                "dispatching test notification to provider: {}",
+                notifier.name
+            );
+
+            let (outcome, attempts, reason) = dispatch_notifier(
+                notifier,
+                notify,
+                &job,
+                resolve_schedule(notifier.name).on_call,
+            );
+
+            // Report the error encountered on the last attempt (if any), so that misconfigured \
+            //   notifiers can be diagnosed
+            let reason = reason.or_else(|| outcome_reason(outcome).map(str::to_string));
+
+            record_outcome(notifier.name, &job, outcome, attempts, reason.clone());
+
+            // Only list skipped notifiers if explicitly targeted
+            if outcome != DispatchOutcome::Skipped || name.is_some() {
+                results.push(DispatchTestResult {
+                    notifier: notifier.name,
+                    attempts,
+                    outcome,
+                    reason,
+                });
+            }
+        }
+    }
+
+    Some(results)
+}
+
+pub fn dispatch(job: DispatchJob) {
+    if APP_CONF.notify.is_some() {
+        let job = Arc::new(job);
+
+        // Enqueue job to all notifiers (this never blocks, as notifiers are processed \
+        //   asynchronously in their own workers; a full queue drops the job)
+        for queue in QUEUES.iter() {
+            match queue.sender.try_send(job.clone()) {
+                Ok(_) => {
+                    debug!("enqueued notification for provider: {}", queue.name);
+                }
+                Err(TrySendError::Full(_)) => {
+                    error!(
+                        "dropped notification for provider: {} (dispatch queue is full)",
+                        queue.name
+                    );
+
+                    record_outcome(
+                        queue.name,
+                        &job,
+                        DispatchOutcome::Dropped,
+                        0,
+                        Some("dispatch queue is full".to_string()),
+                    );
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    error!(
+                        "dropped notification for provider: {} (dispatch workers are gone)",
+                        queue.name
+                    );
+
+                    record_outcome(
+                        queue.name,
+                        &job,
+                        DispatchOutcome::Dropped,
+                        0,
+                        Some("dispatch workers are gone".to_string()),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -6,47 +6,108 @@
 
 use std::time::Duration;
 
-use lettre::message::{Mailbox, Message};
+use lettre::message::header::{Header, HeaderName, HeaderValue};
+use lettre::message::{Mailbox, Message, MultiPart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::transport::smtp::client::{Tls, TlsParameters};
 use lettre::transport::smtp::{Error as SmtpError, SmtpTransport};
 use lettre::{Address, Transport};
+use tera::escape_html;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
-use crate::config::config::ConfigNotify;
+use super::generic::{
+    DispatchAttempt, GenericNotifier, Notification, DISPATCH_ERROR_NOT_CONFIGURED,
+    DISPATCH_TIMEOUT_SECONDS,
+};
+use crate::config::config::{
+    ConfigNotify, ConfigNotifyEmail, ConfigNotifyEmailSMTPTLS, ConfigNotifyEmailTo,
+};
+use crate::prober::status::Status;
 use crate::APP_CONF;
 
+macro_rules! gen_email_header {
+    ($header:ident, $name:expr) => {
+        #[derive(Clone)]
+        struct $header(String);
+
+        impl Header for $header {
+            fn name() -> HeaderName {
+                HeaderName::new_from_ascii_str($name)
+            }
+
+            fn parse(value: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+                Ok($header(value.to_string()))
+            }
+
+            fn display(&self) -> HeaderValue {
+                HeaderValue::new(Self::name(), self.0.to_owned())
+            }
+        }
+    };
+}
+
+gen_email_header!(ListIdHeader, "List-Id");
+gen_email_header!(XPriorityHeader, "X-Priority");
+gen_email_header!(ImportanceHeader, "Importance");
+gen_email_header!(ListUnsubscribeHeader, "List-Unsubscribe");
+gen_email_header!(ListUnsubscribePostHeader, "List-Unsubscribe-Post");
+
+lazy_static! {
+    static ref SUBSCRIBER_TRANSPORT: Option<SmtpTransport> = APP_CONF
+        .notify
+        .as_ref()
+        .and_then(|notify| notify.email.as_ref())
+        .and_then(|email_config| {
+            acquire_configured_transport(
+                email_config,
+                Duration::from_secs(DISPATCH_TIMEOUT_SECONDS),
+            )
+            .map_err(|err| error!("failed to build subscriber email transport: {err}"))
+            .ok()
+        });
+}
+
 pub struct EmailNotifier;
 
 impl GenericNotifier for EmailNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref email_config) = notify.email {
             let nodes_label = notification.replicas.join(", ");
 
-            // Build up the message text
-            let mut message = String::new();
-
-            if notification.startup == true {
-                message.push_str(&format!(
-                    "Status startup alert from: {}\n",
+            // Build up the message title
+            let title = if notification.startup {
+                format!(
+                    "Status startup alert from: {}",
                     APP_CONF.branding.page_title
-                ));
+                )
             } else if notification.changed == true {
-                message.push_str(&format!(
-                    "Status change report from: {}\n",
+                format!(
+                    "Status change report from: {}",
                     APP_CONF.branding.page_title
-                ));
+                )
             } else {
-                message.push_str(&format!(
-                    "Status unchanged reminder from: {}\n",
+                format!(
+                    "Status unchanged reminder from: {}",
                     APP_CONF.branding.page_title
-                ));
-            }
+                )
+            };
+
+            // Build up the message text
+            let mut message = String::new();
 
+            message.push_str(&format!("{}\n", &title));
             message.push_str("\n--\n");
             message.push_str(&format!("Status: {:?}\n", notification.status));
             message.push_str(&format!("Nodes: {}\n", &nodes_label));
             message.push_str(&format!("Time: {}\n", &notification.time));
+
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("Digest: {}\n", digest.summary()));
+            }
+
             message.push_str(&format!("URL: {}", APP_CONF.branding.page_url.as_str()));
 
             message.push_str("\n--\n");
@@ -55,55 +116,122 @@ impl GenericNotifier for EmailNotifier {
 
             debug!("will send email notification with message: {}", &message);
 
-            // Build up the email
-            let email_message = Message::builder()
-                .to(Mailbox::new(
-                    None,
-                    email_config.to.parse::<Address>().or(Err(true))?,
-                ))
-                .from(Mailbox::new(
-                    Some(APP_CONF.branding.page_title.to_owned()),
-                    email_config.from.parse::<Address>().or(Err(true))?,
-                ))
-                .subject(if nodes_label.is_empty() {
-                    notification.status.as_str().to_uppercase()
-                } else {
-                    format!(
-                        "{} | {}",
-                        notification.status.as_str().to_uppercase(),
-                        &nodes_label
-                    )
-                })
-                .body(message)
-                .or(Err(true))?;
+            // Build up the message HTML (from the message text parts)
+            let message_html = make_message_html(notification, &title, &nodes_label);
+
+            // Build up the email subject
+            let subject = if nodes_label.is_empty() {
+                notification.status.as_str().to_uppercase()
+            } else {
+                format!(
+                    "{} | {}",
+                    notification.status.as_str().to_uppercase(),
+                    &nodes_label
+                )
+            };
 
             // Create the transport if not present
-            let transport = match acquire_transport(
-                &email_config.smtp_host,
-                email_config.smtp_port,
-                email_config.smtp_username.to_owned(),
-                email_config.smtp_password.to_owned(),
-                email_config.smtp_encrypt,
-            ) {
+            let transport = match acquire_configured_transport(email_config, attempt.timeout) {
                 Ok(email_config) => email_config,
                 Err(err) => {
                     error!("failed to build email transport: {err}");
 
-                    return Err(true);
+                    return Err(format!("failed to build email transport: {err}"));
                 }
             };
 
-            // Deliver the message
-            if let Err(err) = transport.send(&email_message) {
-                error!("failed to send email: {err}");
+            // Parse all addresses before sending anything (so that an invalid address does not \
+            //   stop the delivery midway)
+            let from_mailbox = Mailbox::new(
+                Some(APP_CONF.branding.page_title.to_owned()),
+                email_config
+                    .from
+                    .parse::<Address>()
+                    .map_err(|err| format!("invalid from address: {err}"))?,
+            );
+
+            let reply_to_mailbox = match email_config.reply_to {
+                Some(ref reply_to) => Some(Mailbox::new(
+                    None,
+                    reply_to
+                        .parse::<Address>()
+                        .map_err(|err| format!("invalid reply to address: {err}"))?,
+                )),
+                None => None,
+            };
+
+            let recipients = list_recipients(&email_config.to);
+
+            let to_mailboxes = notification
+                .on_call_order(&recipients)
+                .map(|to_address| {
+                    to_address
+                        .parse::<Address>()
+                        .map(|address| (*to_address, Mailbox::new(None, address)))
+                        .map_err(|err| format!("invalid to address: {} ({err})", to_address))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            let mut sub_delivery_failure = None;
+
+            // Notice: send one email per recipient, as recipients are listed in escalation \
+            //   order and only escalated recipients should get high-priority emails. Recipients \
+            //   that were delivered on a previous attempt are skipped, as they already got it.
+            for (to_index, (to_address, to_mailbox)) in to_mailboxes.into_iter().enumerate() {
+                if attempt.delivered.contains(to_address) {
+                    continue;
+                }
+
+                // Build up the email
+                let mut email_builder = Message::builder()
+                    .to(to_mailbox)
+                    .from(from_mailbox.to_owned())
+                    .subject(subject.to_owned());
+
+                if let Some(ref reply_to_mailbox) = reply_to_mailbox {
+                    email_builder = email_builder.reply_to(reply_to_mailbox.to_owned());
+                }
+
+                if let Some(ref list_id) = email_config.list_id {
+                    email_builder = email_builder.header(ListIdHeader(list_id.to_owned()));
+                }
+
+                // Mark as high-priority? (escalated reminder)
+                if notification.escalated_for(to_index) {
+                    email_builder = email_builder
+                        .header(XPriorityHeader("1 (Highest)".to_string()))
+                        .header(ImportanceHeader("high".to_string()));
+                }
 
-                return Err(true);
+                let email_message = email_builder
+                    .multipart(MultiPart::alternative_plain_html(
+                        message.to_owned(),
+                        message_html.to_owned(),
+                    ))
+                    .map_err(|err| err.to_string())?;
+
+                // Deliver the message
+                match transport.send(&email_message) {
+                    Ok(_) => {
+                        attempt.delivered.insert(to_address.to_string());
+                    }
+                    Err(err) => {
+                        error!("failed to send email to: {} ({err})", to_address);
+
+                        sub_delivery_failure =
+                            Some(format!("failed to send email to: {} ({err})", to_address));
+                    }
+                }
+            }
+
+            if let Some(sub_delivery_failure) = sub_delivery_failure {
+                return Err(sub_delivery_failure);
             }
 
             return Ok(());
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -119,12 +247,182 @@ impl GenericNotifier for EmailNotifier {
     }
 }
 
+// Notice: subscriber emails are delivered with the same SMTP configuration as email \
+//   notifications, though they are sent to subscribers instead of the configured recipients. \
+//   All subscriber emails share the same transport, which gets built once.
+pub fn send_subscriber_email(
+    email_config: &ConfigNotifyEmail,
+    to_address: &str,
+    subject: &str,
+    message: &str,
+    link: (&str, &str),
+    unsubscribe_url: &str,
+) -> Result<(), ()> {
+    let transport = SUBSCRIBER_TRANSPORT.as_ref().ok_or(())?;
+
+    // Build up the message text
+    let mut message_text = String::new();
+
+    message_text.push_str(&format!("{}\n", message));
+    message_text.push_str("\n--\n");
+    message_text.push_str(&format!("{}: {}\n", link.0, link.1));
+    message_text.push_str(&format!("Unsubscribe: {}", unsubscribe_url));
+
+    // Build up the email
+    let mut email_builder = Message::builder()
+        .to(Mailbox::new(
+            None,
+            to_address.parse::<Address>().or(Err(()))?,
+        ))
+        .from(Mailbox::new(
+            Some(APP_CONF.branding.page_title.to_owned()),
+            email_config.from.parse::<Address>().or(Err(()))?,
+        ))
+        .subject(subject)
+        .header(ListUnsubscribeHeader(format!("<{}>", unsubscribe_url)))
+        .header(ListUnsubscribePostHeader(
+            "List-Unsubscribe=One-Click".to_string(),
+        ));
+
+    if let Some(ref reply_to) = email_config.reply_to {
+        email_builder =
+            email_builder.reply_to(Mailbox::new(None, reply_to.parse::<Address>().or(Err(()))?));
+    }
+
+    let email_message = email_builder
+        .multipart(MultiPart::alternative_plain_html(
+            message_text,
+            make_subscriber_message_html(subject, message, link, unsubscribe_url),
+        ))
+        .or(Err(()))?;
+
+    // Deliver the message
+    transport.send(&email_message).map(|_| ()).map_err(|err| {
+        error!("failed to send subscriber email to: {} ({err})", to_address);
+    })
+}
+
+fn list_recipients(to: &ConfigNotifyEmailTo) -> Vec<&str> {
+    match to {
+        ConfigNotifyEmailTo::Single(to_address) => vec![to_address.as_str()],
+        ConfigNotifyEmailTo::Multiple(to_addresses) => {
+            to_addresses.iter().map(String::as_str).collect()
+        }
+    }
+}
+
+fn make_message_html(notification: &Notification, title: &str, nodes_label: &str) -> String {
+    let mut rows = String::new();
+
+    let mut fields = vec![
+        ("Status", notification.status.as_str().to_uppercase()),
+        ("Nodes", nodes_label.to_string()),
+        ("Time", notification.time.to_owned()),
+    ];
+
+    if let Some(ref digest) = notification.digest {
+        fields.push(("Digest", digest.summary()));
+    }
+
+    for (label, value) in fields {
+        rows.push_str(&format!(
+            "<tr><td style=\"padding: 6px 12px 6px 0; color: #8A8A8A;\">{}</td>\
+            <td style=\"padding: 6px 0; font-weight: bold;\">{}</td></tr>",
+            label,
+            escape_html(&value)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\
+        <html><body style=\"margin: 0; padding: 24px; background: #F5F5F5; font-family: sans-serif; color: #242424;\">\
+        <div style=\"max-width: 560px; margin: 0 auto; background: #FFFFFF; border-top: 4px solid {logo_color};\">\
+        <div style=\"padding: 20px 24px;\"><img src=\"{logo_url}\" alt=\"{page_title}\" height=\"28\" /></div>\
+        <div style=\"padding: 0 24px 24px;\">\
+        <h1 style=\"margin: 0 0 16px; font-size: 18px; color: {status_color};\">{title}</h1>\
+        <table style=\"border-collapse: collapse; font-size: 14px;\">{rows}</table>\
+        <p style=\"margin: 24px 0 0;\"><a href=\"{page_url}\" style=\"color: {logo_color};\">{page_url}</a></p>\
+        </div></div>\
+        <p style=\"max-width: 560px; margin: 16px auto 0; font-size: 12px; color: #8A8A8A;\">\
+        {company_name} — You are receiving this alert as a recipient of status notifications for {page_title}.</p>\
+        </body></html>",
+        logo_color = escape_html(&APP_CONF.branding.logo_color),
+        logo_url = escape_html(APP_CONF.branding.logo_url.as_str()),
+        page_title = escape_html(&APP_CONF.branding.page_title),
+        page_url = escape_html(APP_CONF.branding.page_url.as_str()),
+        company_name = escape_html(&APP_CONF.branding.company_name),
+        status_color = status_to_color(notification.status),
+        title = escape_html(title),
+        rows = rows
+    )
+}
+
+fn make_subscriber_message_html(
+    title: &str,
+    message: &str,
+    link: (&str, &str),
+    unsubscribe_url: &str,
+) -> String {
+    format!(
+        "<!DOCTYPE html>\
+        <html><body style=\"margin: 0; padding: 24px; background: #F5F5F5; font-family: sans-serif; color: #242424;\">\
+        <div style=\"max-width: 560px; margin: 0 auto; background: #FFFFFF; border-top: 4px solid {logo_color};\">\
+        <div style=\"padding: 20px 24px;\"><img src=\"{logo_url}\" alt=\"{page_title}\" height=\"28\" /></div>\
+        <div style=\"padding: 0 24px 24px;\">\
+        <h1 style=\"margin: 0 0 16px; font-size: 18px;\">{title}</h1>\
+        <p style=\"margin: 0; font-size: 14px; line-height: 20px;\">{message}</p>\
+        <p style=\"margin: 24px 0 0;\"><a href=\"{link_url}\" style=\"color: {logo_color};\">{link_label}</a></p>\
+        </div></div>\
+        <p style=\"max-width: 560px; margin: 16px auto 0; font-size: 12px; color: #8A8A8A;\">\
+        {company_name} — <a href=\"{unsubscribe_url}\" style=\"color: #8A8A8A;\">Unsubscribe from these updates</a></p>\
+        </body></html>",
+        logo_color = escape_html(&APP_CONF.branding.logo_color),
+        logo_url = escape_html(APP_CONF.branding.logo_url.as_str()),
+        page_title = escape_html(&APP_CONF.branding.page_title),
+        company_name = escape_html(&APP_CONF.branding.company_name),
+        title = escape_html(title),
+        message = escape_html(message).replace('\n', "<br />"),
+        link_label = escape_html(link.0),
+        link_url = escape_html(link.1),
+        unsubscribe_url = escape_html(unsubscribe_url)
+    )
+}
+
+fn status_to_color(status: &Status) -> &'static str {
+    match *status {
+        Status::Healthy => "#54A158",
+        Status::Sick => "#D5A048",
+        Status::Dead => "#C4291C",
+    }
+}
+
+fn acquire_configured_transport(
+    email_config: &ConfigNotifyEmail,
+    timeout: Duration,
+) -> Result<SmtpTransport, SmtpError> {
+    acquire_transport(
+        &email_config.smtp_host,
+        email_config.smtp_port,
+        email_config.smtp_username.to_owned(),
+        email_config.smtp_password.to_owned(),
+        email_config
+            .smtp_tls
+            .unwrap_or(if email_config.smtp_encrypt {
+                ConfigNotifyEmailSMTPTLS::StartTLS
+            } else {
+                ConfigNotifyEmailSMTPTLS::Opportunistic
+            }),
+        timeout,
+    )
+}
+
 fn acquire_transport(
     smtp_host: &str,
     smtp_port: u16,
     smtp_username: Option<String>,
     smtp_password: Option<String>,
-    smtp_encrypt: bool,
+    smtp_tls: ConfigNotifyEmailSMTPTLS,
+    timeout: Duration,
 ) -> Result<SmtpTransport, SmtpError> {
     // Acquire credentials (if any)
     let credentials = if let (Some(smtp_username_value), Some(smtp_password_value)) =
@@ -139,17 +437,20 @@ fn acquire_transport(
     };
 
     // Acquire TLS wrapper (may fail)
-    let tls_wrapper = match TlsParameters::new(smtp_host.into()) {
-        Ok(parameters) if smtp_encrypt => Tls::Required(parameters),
-        Ok(parameters) => Tls::Opportunistic(parameters),
-        Err(e) => return Err(e),
+    let tls_wrapper = match smtp_tls {
+        ConfigNotifyEmailSMTPTLS::Implicit => Tls::Wrapper(TlsParameters::new(smtp_host.into())?),
+        ConfigNotifyEmailSMTPTLS::StartTLS => Tls::Required(TlsParameters::new(smtp_host.into())?),
+        ConfigNotifyEmailSMTPTLS::Opportunistic => {
+            Tls::Opportunistic(TlsParameters::new(smtp_host.into())?)
+        }
+        ConfigNotifyEmailSMTPTLS::None => Tls::None,
     };
 
     // Build transport
     let mut mailer = SmtpTransport::builder_dangerous(smtp_host)
         .port(smtp_port)
         .tls(tls_wrapper)
-        .timeout(Some(Duration::from_secs(DISPATCH_TIMEOUT_SECONDS)));
+        .timeout(Some(timeout));
 
     if let Some(credentials_value) = credentials {
         mailer = mailer.credentials(credentials_value);
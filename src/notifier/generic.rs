@@ -4,8 +4,11 @@
 // Copyright: 2018, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+use std::collections::HashSet;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Response;
 
 use crate::config::config::ConfigNotify;
 use crate::prober::status::Status;
@@ -13,6 +16,7 @@ use crate::prober::status::Status;
 const DISPATCH_TRY_WAIT_SECONDS: u64 = 2;
 const DISPATCH_TRY_ATTEMPT_TIMES: u8 = 3;
 pub const DISPATCH_TIMEOUT_SECONDS: u64 = 10;
+pub const DISPATCH_ERROR_NOT_CONFIGURED: &str = "notifier is not configured";
 
 pub struct Notification<'a> {
     pub status: &'a Status,
@@ -21,19 +25,85 @@ pub struct Notification<'a> {
     pub changed: bool,
     pub escalated: Option<u16>,
     pub startup: bool,
+    pub on_call: usize,
+    pub digest: Option<NotificationDigest>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct NotificationDigest {
+    pub changes: u32,
+    pub dead: u32,
+    pub sick: u32,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum DispatchOutcome {
+    #[serde(rename = "delivered")]
+    Delivered,
+
+    #[serde(rename = "failed")]
+    Failed,
+
+    #[serde(rename = "timed_out")]
+    TimedOut,
+
+    #[serde(rename = "dropped")]
+    Dropped,
+
+    #[serde(rename = "skipped")]
+    Skipped,
+
+    #[serde(rename = "muted")]
+    Muted,
+}
+
+pub struct DispatchAttempt {
+    pub timeout: Duration,
+
+    // Notice: targets delivered on a previous attempt are listed there, so that notifiers \
+    //   sending to multiple targets only retry the targets that failed.
+    pub delivered: HashSet<String>,
 }
 
 pub trait GenericNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool>;
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String>;
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool;
     fn name() -> &'static str;
 }
 
+pub fn check_http_response(response: reqwest::Result<Response>) -> Result<(), String> {
+    // Notice: request URLs are stripped from errors, as some notifiers pass their secret \
+    //   tokens in URLs (errors get listed in the notification delivery log).
+    match response {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => Err(format!(
+            "got non-success http status: {}",
+            response.status()
+        )),
+        Err(err) if err.is_timeout() => Err("http request timed out".to_string()),
+        Err(err) => Err(err.without_url().to_string()),
+    }
+}
+
+impl NotificationDigest {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} status changes grouped ({} services dead, {} sick)",
+            self.changes, self.dead, self.sick
+        )
+    }
+}
+
 impl<'a> Notification<'a> {
     pub fn dispatch<N: GenericNotifier>(
         notify: &ConfigNotify,
         notification: &Notification,
-    ) -> Result<(), bool> {
+        timeout: Duration,
+    ) -> (DispatchOutcome, u8, Option<String>) {
         if N::can_notify(notify, notification) == true {
             info!(
                 "dispatch {} notification for status: {:?} and replicas: {:?}",
@@ -42,6 +112,15 @@ impl<'a> Notification<'a> {
                 notification.replicas
             );
 
+            let deadline = Instant::now() + timeout;
+
+            let mut attempt = DispatchAttempt {
+                timeout,
+                delivered: HashSet::new(),
+            };
+
+            let mut last_error = None;
+
             for try_index in 1..(DISPATCH_TRY_ATTEMPT_TIMES + 1) {
                 debug!(
                     "dispatch {} notification attempt: #{}",
@@ -51,25 +130,75 @@ impl<'a> Notification<'a> {
 
                 // Hold on for next try
                 if try_index > 1 {
-                    thread::sleep(Duration::from_secs(DISPATCH_TRY_WAIT_SECONDS))
+                    let try_wait = Duration::from_secs(DISPATCH_TRY_WAIT_SECONDS);
+
+                    // Give up if the next try would start past the dispatch deadline
+                    if Instant::now() + try_wait >= deadline {
+                        error!(
+                            "timed out dispatching notification to provider: {} (after {} attempts)",
+                            N::name(),
+                            try_index - 1
+                        );
+
+                        return (DispatchOutcome::TimedOut, try_index - 1, last_error);
+                    }
+
+                    thread::sleep(try_wait)
                 }
 
-                // Attempt notification dispatch
-                if N::attempt(notify, notification).is_ok() == true {
-                    debug!("dispatched notification to provider: {}", N::name());
+                // Notice: attempts cannot be interrupted, thus notifier transports are given the \
+                //   time left before the dispatch deadline as their own timeout, which bounds \
+                //   the attempt if the provider hangs (eg. on a stalled SMTP server).
+                attempt.timeout = deadline
+                    .saturating_duration_since(Instant::now())
+                    .min(Duration::from_secs(DISPATCH_TIMEOUT_SECONDS));
 
-                    return Ok(());
+                // Attempt notification dispatch
+                match N::attempt(notify, notification, &mut attempt) {
+                    Ok(_) => {
+                        debug!("dispatched notification to provider: {}", N::name());
+
+                        return (DispatchOutcome::Delivered, try_index, None);
+                    }
+                    Err(err) => {
+                        warn!(
+                            "dispatch {} notification attempt #{} failed: {}",
+                            N::name(),
+                            try_index,
+                            err
+                        );
+
+                        last_error = Some(err);
+                    }
                 }
             }
 
+            // Last attempt was cut by the dispatch deadline?
+            if Instant::now() >= deadline {
+                error!(
+                    "timed out dispatching notification to provider: {} (on last attempt)",
+                    N::name()
+                );
+
+                return (
+                    DispatchOutcome::TimedOut,
+                    DISPATCH_TRY_ATTEMPT_TIMES,
+                    last_error,
+                );
+            }
+
             error!("failed dispatching notification to provider: {}", N::name());
 
-            return Err(true);
+            return (
+                DispatchOutcome::Failed,
+                DISPATCH_TRY_ATTEMPT_TIMES,
+                last_error,
+            );
         }
 
         debug!("did not dispatch notification to provider: {}", N::name());
 
-        Err(false)
+        (DispatchOutcome::Skipped, 0, None)
     }
 
     pub fn expected(&self, reminders_only: bool) -> bool {
@@ -82,16 +211,29 @@ impl<'a> Notification<'a> {
         }
     }
 
+    pub fn on_call_order<'b, T>(&self, targets: &'b [T]) -> impl Iterator<Item = &'b T> {
+        // Rotate targets so that the target currently on call comes first, then the next \
+        //   targets in rotation order (targets are not rotated if no schedule is configured, as \
+        //   the first target is always on call then).
+        let on_call_index = self.on_call.checked_rem(targets.len()).unwrap_or(0);
+
+        targets
+            .iter()
+            .cycle()
+            .skip(on_call_index)
+            .take(targets.len())
+    }
+
     pub fn escalated_for(&self, target_index: usize) -> bool {
         // In case reminder escalation is enabled and counter is currently active, check if \
-        //   the passed target index is within range of the current escalation count. As the \
-        //   outage persists and the escalation count increments, then the notifier will tend to \
-        //   notify all targets in range at each reminder tick, while on first reminder only the \
-        //   first target will get the high-priority alert (others will still get reminded but \
-        //   under a normal priority alert). This can be used to page (ie. wake-up at night) \
-        //   the defined always-on-call person first, and then if that person does not respond or \
-        //   does not fix the issue in due time, then other targets will get progressively paged \
-        //   one after the other as the outage persists.
+        //   the passed target index (in on-call order) is within range of the current \
+        //   escalation count. As the outage persists and the escalation count increments, then \
+        //   the notifier will tend to notify all targets in range at each reminder tick, while \
+        //   on first reminder only the first target will get the high-priority alert (others \
+        //   will still get reminded but under a normal priority alert). This can be used to \
+        //   page (ie. wake-up at night) the person currently on call first, and then if that \
+        //   person does not respond or does not fix the issue in due time, then other targets \
+        //   will get progressively paged one after the other as the outage persists.
         if self.changed == false {
             if let Some(escalated) = self.escalated {
                 // Escalation is enabled, only escalate to target if in range
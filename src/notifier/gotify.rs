@@ -10,7 +10,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
@@ -25,7 +28,11 @@ lazy_static! {
 pub struct GotifyNotifier;
 
 impl GenericNotifier for GotifyNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref gotify) = notify.gotify {
             // Build up the message text
             let mut message = String::new();
@@ -43,6 +50,10 @@ impl GenericNotifier for GotifyNotifier {
             message.push_str(&format!("Nodes:\n{}\n", &notification.replicas.join("\n")));
             message.push_str(&format!("Time: {}", &notification.time));
 
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("\nDigest: {}", digest.summary()));
+            }
+
             debug!("will send Gotify notification with message: {}", &message);
 
             // Generate URL
@@ -65,20 +76,16 @@ impl GenericNotifier for GotifyNotifier {
             }
 
             // Submit message to Gotify
-            let response = GOTIFY_HTTP_CLIENT.post(&url).form(&params).send();
-
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() != true {
-                    return Err(true);
-                }
-            } else {
-                return Err(true);
-            }
+            let response = GOTIFY_HTTP_CLIENT
+                .post(&url)
+                .form(&params)
+                .timeout(attempt.timeout)
+                .send();
 
-            return Ok(());
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
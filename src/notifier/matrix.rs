@@ -10,7 +10,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
@@ -24,7 +27,8 @@ lazy_static! {
         format_status,
         format_replicas,
         format_status_page,
-        format_time
+        format_time,
+        format_digest
     ];
 }
 
@@ -35,7 +39,11 @@ static MATRIX_MESSAGE_FORMAT: &'static str = "org.matrix.custom.html";
 pub struct MatrixNotifier;
 
 impl GenericNotifier for MatrixNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref matrix) = notify.matrix {
             // Build up the message text
             let message = format_message(notification);
@@ -60,20 +68,16 @@ impl GenericNotifier for MatrixNotifier {
             params.insert("formatted_body", &message);
 
             // Submit message to Matrix
-            let response = MATRIX_HTTP_CLIENT.post(&url).json(&params).send();
+            let response = MATRIX_HTTP_CLIENT
+                .post(&url)
+                .json(&params)
+                .timeout(attempt.timeout)
+                .send();
 
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() != true {
-                    return Err(true);
-                }
-            } else {
-                return Err(true);
-            }
-
-            return Ok(());
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -144,6 +148,14 @@ fn format_time(notification: &Notification) -> String {
     format!("<p>Time: {}</p>", notification.time)
 }
 
+fn format_digest(notification: &Notification) -> String {
+    if let Some(ref digest) = notification.digest {
+        format!("<p>Digest: {}</p>", digest.summary())
+    } else {
+        "".to_string()
+    }
+}
+
 fn format_message(notification: &Notification) -> String {
     MATRIX_FORMATTERS
         .iter()
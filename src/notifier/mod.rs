@@ -4,7 +4,9 @@
 // Copyright: 2018, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+pub mod dispatcher;
 pub mod generic;
+pub mod schedule;
 
 #[cfg(feature = "notifier-email")]
 pub mod email;
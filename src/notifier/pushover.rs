@@ -9,7 +9,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::prober::status::Status;
 use crate::APP_CONF;
@@ -27,7 +30,11 @@ static PUSHOVER_API_URL: &'static str = "https://api.pushover.net/1/messages.jso
 pub struct PushoverNotifier;
 
 impl GenericNotifier for PushoverNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref pushover) = notify.pushover {
             // Build up the message text
             let mut message = String::new();
@@ -49,11 +56,17 @@ impl GenericNotifier for PushoverNotifier {
             ));
             message.push_str(&format!("<u>Time:</u> {}", &notification.time));
 
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("\n<u>Digest:</u> {}", digest.summary()));
+            }
+
             debug!("will send Pushover notification with message: {}", &message);
 
-            let mut has_sub_delivery_failure = false;
+            let mut sub_delivery_failure = None;
 
-            for (user_index, user_key) in pushover.user_keys.iter().enumerate() {
+            for (user_index, user_key) in
+                notification.on_call_order(&pushover.user_keys).enumerate()
+            {
                 // Build form parameters
                 let mut params: HashMap<&str, &str> = HashMap::new();
 
@@ -81,26 +94,23 @@ impl GenericNotifier for PushoverNotifier {
                 let response = PUSHOVER_HTTP_CLIENT
                     .post(PUSHOVER_API_URL)
                     .form(&params)
+                    .timeout(attempt.timeout)
                     .send();
 
-                // Check for any failure
-                if let Ok(response_inner) = response {
-                    if response_inner.status().is_success() != true {
-                        has_sub_delivery_failure = true;
-                    }
-                } else {
-                    has_sub_delivery_failure = true;
+                // Check for any failure (the last failure is reported)
+                if let Err(err) = check_http_response(response) {
+                    sub_delivery_failure = Some(err);
                 }
             }
 
-            if has_sub_delivery_failure == true {
-                return Err(true);
+            if let Some(sub_delivery_failure) = sub_delivery_failure {
+                return Err(sub_delivery_failure);
             }
 
             return Ok(());
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
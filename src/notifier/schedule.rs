@@ -0,0 +1,173 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use chrono::{NaiveDateTime, NaiveTime, Utc};
+
+use crate::config::config::ConfigNotifySchedule;
+use crate::APP_CONF;
+
+const SECONDS_IN_DAY: i64 = 86400;
+
+#[derive(Default)]
+pub struct ScheduleState {
+    pub on_call: usize,
+    pub muted: bool,
+}
+
+pub fn resolve(notifier: &str) -> ScheduleState {
+    if let Some(schedule) = find_schedule(notifier) {
+        // Notice: schedules are evaluated in their local time, which lets rotations and quiet \
+        //   hours follow daylight saving time changes (eg. a shift handover at 09:00 is always \
+        //   at 09:00 on the wall clock of the team being on call).
+        let now = Utc::now().with_timezone(&schedule.timezone).naive_local();
+
+        let state = ScheduleState {
+            on_call: resolve_on_call(schedule, &now),
+            muted: resolve_quiet_hours(schedule, &now.time()),
+        };
+
+        debug!(
+            "resolved schedule for notifier: {} (on call: #{}, muted: {})",
+            notifier, state.on_call, state.muted
+        );
+
+        state
+    } else {
+        ScheduleState::default()
+    }
+}
+
+fn find_schedule(notifier: &str) -> Option<&'static ConfigNotifySchedule> {
+    APP_CONF.notify.as_ref().and_then(|notify| {
+        notify.schedule.iter().find(|schedule| {
+            schedule
+                .notifiers
+                .iter()
+                .any(|schedule_notifier| schedule_notifier == notifier)
+        })
+    })
+}
+
+fn resolve_on_call(schedule: &ConfigNotifySchedule, now: &NaiveDateTime) -> usize {
+    if let Some(rotation_start) = schedule.rotation_start {
+        let shift_seconds = schedule.rotation_days as i64 * SECONDS_IN_DAY;
+        let elapsed_seconds = (*now - rotation_start).num_seconds();
+
+        // Count the number of shifts elapsed since the rotation started (the first target \
+        //   is on call during the first shift, then the second target, etc.)
+        if shift_seconds > 0 && elapsed_seconds > 0 {
+            return (elapsed_seconds / shift_seconds) as usize;
+        }
+    }
+
+    0
+}
+
+fn resolve_quiet_hours(schedule: &ConfigNotifySchedule, time: &NaiveTime) -> bool {
+    if let (Some(quiet_from), Some(quiet_to)) = (schedule.quiet_hours_from, schedule.quiet_hours_to)
+    {
+        if quiet_from <= quiet_to {
+            // Quiet hours are within the same day (eg. 12:00 to 14:00)
+            *time >= quiet_from && *time < quiet_to
+        } else {
+            // Quiet hours wrap around midnight (eg. 22:00 to 07:00)
+            *time >= quiet_from || *time < quiet_to
+        }
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+    use chrono_tz::Europe::Paris;
+
+    fn make_schedule(
+        rotation_start: Option<NaiveDateTime>,
+        quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    ) -> ConfigNotifySchedule {
+        ConfigNotifySchedule {
+            notifiers: vec!["email".to_string()],
+            timezone: Paris,
+            rotation_start,
+            rotation_days: 7,
+            quiet_hours_from: quiet_hours.map(|(from, _)| from),
+            quiet_hours_to: quiet_hours.map(|(_, to)| to),
+        }
+    }
+
+    fn make_time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn make_date_time(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_time(make_time(hour, minute))
+    }
+
+    #[test]
+    fn it_resolves_quiet_hours_within_day() {
+        let schedule = make_schedule(None, Some((make_time(12, 0), make_time(14, 0))));
+
+        assert!(!resolve_quiet_hours(&schedule, &make_time(11, 59)));
+        assert!(resolve_quiet_hours(&schedule, &make_time(12, 0)));
+        assert!(resolve_quiet_hours(&schedule, &make_time(13, 59)));
+        assert!(!resolve_quiet_hours(&schedule, &make_time(14, 0)));
+    }
+
+    #[test]
+    fn it_resolves_quiet_hours_overnight() {
+        let schedule = make_schedule(None, Some((make_time(22, 0), make_time(7, 0))));
+
+        assert!(!resolve_quiet_hours(&schedule, &make_time(21, 59)));
+        assert!(resolve_quiet_hours(&schedule, &make_time(22, 0)));
+        assert!(resolve_quiet_hours(&schedule, &make_time(0, 0)));
+        assert!(resolve_quiet_hours(&schedule, &make_time(6, 59)));
+        assert!(!resolve_quiet_hours(&schedule, &make_time(7, 0)));
+    }
+
+    #[test]
+    fn it_resolves_no_quiet_hours() {
+        let schedule = make_schedule(None, None);
+
+        assert!(!resolve_quiet_hours(&schedule, &make_time(3, 0)));
+    }
+
+    #[test]
+    fn it_resolves_on_call_rotation_order() {
+        let schedule = make_schedule(Some(make_date_time(2, 9, 0)), None);
+
+        // Rotation did not start yet
+        assert_eq!(resolve_on_call(&schedule, &make_date_time(1, 9, 0)), 0);
+
+        assert_eq!(resolve_on_call(&schedule, &make_date_time(2, 9, 0)), 0);
+        assert_eq!(resolve_on_call(&schedule, &make_date_time(9, 8, 59)), 0);
+        assert_eq!(resolve_on_call(&schedule, &make_date_time(9, 9, 0)), 1);
+        assert_eq!(resolve_on_call(&schedule, &make_date_time(16, 9, 0)), 2);
+    }
+
+    #[test]
+    fn it_resolves_on_call_across_daylight_saving_time() {
+        // Clocks move forward on March 29 in Paris, though shifts still change at 09:00
+        let schedule = make_schedule(Some(make_date_time(23, 9, 0)), None);
+
+        let (before_handover, at_handover) = (
+            Paris.from_utc_datetime(&make_date_time(30, 6, 59)),
+            Paris.from_utc_datetime(&make_date_time(30, 7, 0)),
+        );
+
+        assert_eq!(before_handover.naive_local(), make_date_time(30, 8, 59));
+
+        assert_eq!(
+            resolve_on_call(&schedule, &before_handover.naive_local()),
+            0
+        );
+        assert_eq!(resolve_on_call(&schedule, &at_handover.naive_local()), 1);
+    }
+}
@@ -8,7 +8,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::prober::status::Status;
 use crate::APP_CONF;
@@ -44,9 +47,14 @@ struct SlackPayloadAttachmentField<'a> {
 }
 
 impl GenericNotifier for SlackNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref slack) = notify.slack {
             let status_label = format!("{:?}", notification.status);
+            let digest_label = notification.digest.map(|digest| digest.summary());
             let mut nodes_label = String::new();
 
             // Build message
@@ -104,6 +112,14 @@ impl GenericNotifier for SlackNotifier {
                 short: true,
             });
 
+            if let Some(ref digest_label) = digest_label {
+                attachment.fields.push(SlackPayloadAttachmentField {
+                    title: "Digest",
+                    value: digest_label,
+                    short: false,
+                });
+            }
+
             attachment.fields.push(SlackPayloadAttachmentField {
                 title: "Monitor Page",
                 value: APP_CONF.branding.page_url.as_str(),
@@ -117,18 +133,13 @@ impl GenericNotifier for SlackNotifier {
             let response = SLACK_HTTP_CLIENT
                 .post(slack.hook_url.as_str())
                 .json(&payload)
+                .timeout(attempt.timeout)
                 .send();
 
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() == true {
-                    return Ok(());
-                }
-            }
-
-            return Err(true);
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -8,7 +8,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
@@ -42,7 +45,11 @@ enum TelegramChatID<'a> {
 }
 
 impl GenericNotifier for TelegramNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref telegram) = notify.telegram {
             // Build message
             let mut message = if notification.startup == true {
@@ -73,6 +80,11 @@ impl GenericNotifier for TelegramNotifier {
                 .join("\n");
 
             message.push_str(&nodes_list_text);
+
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("\nDigest: {}", digest.summary()));
+            }
+
             message.push_str(&format!("\nLink: {}", APP_CONF.branding.page_url.as_str()));
 
             debug!("will send Telegram notification with message: {}", &message);
@@ -102,19 +114,14 @@ impl GenericNotifier for TelegramNotifier {
             let response = TELEGRAM_HTTP_CLIENT
                 .post(url.as_str())
                 .json(&payload)
+                .timeout(attempt.timeout)
                 .send();
 
             // Check for any failure
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() == true {
-                    return Ok(());
-                }
-            }
-
-            return Err(true);
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
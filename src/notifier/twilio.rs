@@ -9,7 +9,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
@@ -28,7 +31,11 @@ lazy_static! {
 pub struct TwilioNotifier;
 
 impl GenericNotifier for TwilioNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref twilio) = notify.twilio {
             // Build up the message text
             let mut message = String::new();
@@ -45,6 +52,10 @@ impl GenericNotifier for TwilioNotifier {
             message.push_str(&format!("Nodes: {}\n", &notification.replicas.join(", ")));
             message.push_str(&format!("Time: {}\n", &notification.time));
 
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("Digest: {}\n", digest.summary()));
+            }
+
             // Trim down message to a maximum length? (most SMS receivers and networks support \
             //   up to 1600 characters by re-building message segments)
             if message.len() > TEXT_MESSAGE_MAXIMUM_LENGTH {
@@ -61,9 +72,9 @@ impl GenericNotifier for TwilioNotifier {
 
             debug!("will send Twilio notification with message: {}", &message);
 
-            let mut has_sub_delivery_failure = false;
+            let mut sub_delivery_failure = None;
 
-            for to_number in &twilio.to {
+            for to_number in notification.on_call_order(&twilio.to) {
                 // Build form parameters
                 let mut params = HashMap::new();
 
@@ -79,26 +90,23 @@ impl GenericNotifier for TwilioNotifier {
                         Some(twilio.auth_token.as_str()),
                     )
                     .form(&params)
+                    .timeout(attempt.timeout)
                     .send();
 
-                // Check for any failure
-                if let Ok(response_inner) = response {
-                    if response_inner.status().is_success() != true {
-                        has_sub_delivery_failure = true;
-                    }
-                } else {
-                    has_sub_delivery_failure = true;
+                // Check for any failure (the last failure is reported)
+                if let Err(err) = check_http_response(response) {
+                    sub_delivery_failure = Some(err);
                 }
             }
 
-            if has_sub_delivery_failure == true {
-                return Err(true);
+            if let Some(sub_delivery_failure) = sub_delivery_failure {
+                return Err(sub_delivery_failure);
             }
 
             return Ok(());
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -9,7 +9,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
@@ -31,7 +34,11 @@ struct WebExPayload<'a> {
 }
 
 impl GenericNotifier for WebExNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref webex) = notify.webex {
             let nodes_label = notification.replicas.join(", ");
 
@@ -58,6 +65,11 @@ impl GenericNotifier for WebExNotifier {
             message.push_str(&format!("Status: {:?}\n", notification.status));
             message.push_str(&format!("Nodes: {}\n", &nodes_label));
             message.push_str(&format!("Time: {}\n", &notification.time));
+
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("Digest: {}\n", digest.summary()));
+            }
+
             message.push_str(&format!("URL: {}", APP_CONF.branding.page_url.as_str()));
 
             // Build paylaod
@@ -71,18 +83,13 @@ impl GenericNotifier for WebExNotifier {
                 .post(webex.endpoint_url.as_str())
                 .header("Authorization", "Bearer ".to_owned() + webex.token.as_str())
                 .json(&payload)
+                .timeout(attempt.timeout)
                 .send();
 
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() == true {
-                    return Ok(());
-                }
-            }
-
-            return Err(true);
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -8,7 +8,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification, NotificationDigest,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::prober::status::Status;
 use crate::APP_CONF;
@@ -32,6 +35,9 @@ struct WebHookPayload<'a> {
     time: &'a str,
     replicas: &'a [&'a str],
     page: WebHookPayloadPage<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    digest: Option<NotificationDigest>,
 }
 
 #[derive(Serialize)]
@@ -53,7 +59,11 @@ struct WebHookPayloadPage<'a> {
 }
 
 impl GenericNotifier for WebHookNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref webhook) = notify.webhook {
             // Acquire hook type
             let hook_type = if notification.startup == true {
@@ -74,24 +84,20 @@ impl GenericNotifier for WebHookNotifier {
                     title: APP_CONF.branding.page_title.as_str(),
                     url: APP_CONF.branding.page_url.as_str(),
                 },
+                digest: notification.digest,
             };
 
             // Submit payload to Web Hooks
             let response = WEBHOOK_HTTP_CLIENT
                 .post(webhook.hook_url.as_str())
                 .json(&payload)
+                .timeout(attempt.timeout)
                 .send();
 
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() == true {
-                    return Ok(());
-                }
-            }
-
-            return Err(true);
+            return check_http_response(response);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, _: &Notification) -> bool {
@@ -5,20 +5,29 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 use std::sync::RwLock;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use libstrophe::{Connection, ConnectionEvent, Context, Stanza};
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    DispatchAttempt, GenericNotifier, Notification, DISPATCH_ERROR_NOT_CONFIGURED,
+};
 use crate::config::config::ConfigNotify;
 use crate::APP_CONF;
 
+const XMPP_RUN_STEP_MILLISECONDS: u64 = 100;
+
 pub struct XMPPNotifier;
 
 impl GenericNotifier for XMPPNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref xmpp) = notify.xmpp {
             let is_sent = RwLock::new(false);
+            let is_stopped = RwLock::new(false);
 
             // Build up the message text
             let mut message = String::new();
@@ -34,6 +43,11 @@ impl GenericNotifier for XMPPNotifier {
             message.push_str(&format!("Status: {:?}\n", notification.status));
             message.push_str(&format!("Nodes: {}\n", &notification.replicas.join(", ")));
             message.push_str(&format!("Time: {}\n", &notification.time));
+
+            if let Some(ref digest) = notification.digest {
+                message.push_str(&format!("Digest: {}\n", digest.summary()));
+            }
+
             message.push_str(&format!("URL: {}", APP_CONF.branding.page_url.as_str()));
 
             debug!("will send XMPP notification with message: {}", &message);
@@ -84,6 +98,8 @@ impl GenericNotifier for XMPPNotifier {
                                 debug!("disconnected from XMPP account: {}", &xmpp.from);
                             }
 
+                            *is_stopped.write().unwrap() = true;
+
                             context.stop();
                         }
                         _ => {}
@@ -97,25 +113,28 @@ impl GenericNotifier for XMPPNotifier {
             connection.set_jid(&xmpp.from);
             connection.set_pass(&xmpp.xmpp_password);
 
-            connection.set_keepalive(
-                Duration::from_secs(DISPATCH_TIMEOUT_SECONDS),
-                Duration::from_secs(DISPATCH_TIMEOUT_SECONDS / 2),
-            );
+            connection.set_keepalive(attempt.timeout, attempt.timeout / 2);
 
             // Connect to XMPP server
             if let Ok(mut connection_context) = connection.connect_client(None, None, &fn_handle) {
-                // Enter context
-                connection_context.run();
+                // Enter context (until disconnected, or the attempt times out)
+                let deadline = Instant::now() + attempt.timeout;
+
+                while *is_stopped.read().unwrap() == false && Instant::now() < deadline {
+                    connection_context.run_once(Duration::from_millis(XMPP_RUN_STEP_MILLISECONDS));
+                }
 
                 if *is_sent.read().unwrap() == true {
                     return Ok(());
                 }
+
+                return Err("message was not sent".to_string());
             }
 
-            return Err(true);
+            return Err("could not connect to xmpp server".to_string());
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
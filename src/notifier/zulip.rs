@@ -9,7 +9,10 @@ use std::time::Duration;
 
 use reqwest::blocking::Client;
 
-use super::generic::{GenericNotifier, Notification, DISPATCH_TIMEOUT_SECONDS};
+use super::generic::{
+    check_http_response, DispatchAttempt, GenericNotifier, Notification,
+    DISPATCH_ERROR_NOT_CONFIGURED, DISPATCH_TIMEOUT_SECONDS,
+};
 use crate::config::config::ConfigNotify;
 use crate::prober::status::Status;
 use crate::APP_CONF;
@@ -34,7 +37,11 @@ struct ZulipPayload<'a> {
 }
 
 impl GenericNotifier for ZulipNotifier {
-    fn attempt(notify: &ConfigNotify, notification: &Notification) -> Result<(), bool> {
+    fn attempt(
+        notify: &ConfigNotify,
+        notification: &Notification,
+        attempt: &mut DispatchAttempt,
+    ) -> Result<(), String> {
         if let Some(ref zulip) = notify.zulip {
             let status_label = format!("{:?}", notification.status);
 
@@ -62,6 +69,11 @@ impl GenericNotifier for ZulipNotifier {
 
             message_text.push_str(&format!("\n **Status**: {}", &status_label));
             message_text.push_str(&format!("\n **Time**: {}", &notification.time));
+
+            if let Some(ref digest) = notification.digest {
+                message_text.push_str(&format!("\n **Digest**: {}", digest.summary()));
+            }
+
             message_text.push_str(&format!(
                 "\n **Page**: {}",
                 &APP_CONF.branding.page_url.as_str()
@@ -79,23 +91,25 @@ impl GenericNotifier for ZulipNotifier {
                 .post(zulip.api_url.join("messages").unwrap().as_str())
                 .basic_auth(zulip.bot_email.clone(), Some(zulip.bot_api_key.clone()))
                 .form(&payload)
+                .timeout(attempt.timeout)
                 .send();
 
-            if let Ok(response_inner) = response {
-                if response_inner.status().is_success() == true {
-                    return Ok(());
-                } else {
+            match response {
+                Ok(response_inner) if !response_inner.status().is_success() => {
+                    let status = response_inner.status();
+
                     warn!(
                         "could not submit data to zulip: {:?}",
                         response_inner.text()
                     );
+
+                    return Err(format!("got non-success http status: {}", status));
                 }
+                response => return check_http_response(response),
             }
-
-            return Err(true);
         }
 
-        Err(false)
+        Err(DISPATCH_ERROR_NOT_CONFIGURED.to_string())
     }
 
     fn can_notify(notify: &ConfigNotify, notification: &Notification) -> bool {
@@ -0,0 +1,135 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use super::status::Status;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Aggregate {
+    #[serde(rename = "worst")]
+    Worst,
+
+    #[serde(rename = "quorum")]
+    Quorum,
+
+    #[serde(rename = "minimum")]
+    Minimum,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub enum AggregatePolicy {
+    #[serde(rename = "worst")]
+    Worst,
+
+    #[serde(rename = "quorum")]
+    Quorum(u8),
+
+    #[serde(rename = "minimum")]
+    Minimum(u32),
+}
+
+impl AggregatePolicy {
+    pub fn from_config(
+        aggregate: Aggregate,
+        quorum: Option<u8>,
+        minimum: Option<u32>,
+    ) -> Result<Self, &'static str> {
+        match aggregate {
+            Aggregate::Worst => Ok(AggregatePolicy::Worst),
+            Aggregate::Quorum => match quorum {
+                Some(quorum) if quorum > 0 && quorum <= 100 => Ok(AggregatePolicy::Quorum(quorum)),
+                _ => Err("quorum aggregate requires aggregate_quorum between 1 and 100"),
+            },
+            Aggregate::Minimum => match minimum {
+                Some(minimum) if minimum > 0 => Ok(AggregatePolicy::Minimum(minimum)),
+                _ => Err("minimum aggregate requires a non-zero aggregate_minimum"),
+            },
+        }
+    }
+
+    pub fn resolve(
+        &self,
+        worst_status: Status,
+        children: usize,
+        dead_children: usize,
+        healthy_children: usize,
+    ) -> Status {
+        // Notice: policies only apply when some children are dead, as they decide whether the \
+        //   parent is dead or only sick (degraded). The quorum considers children that are not \
+        //   dead (ie. healthy or sick) as up, as they are still able to serve requests, while \
+        //   the minimum only counts healthy children.
+        if worst_status != Status::Dead {
+            return worst_status;
+        }
+
+        let up_children = children - dead_children;
+
+        let has_enough_up = match self {
+            AggregatePolicy::Worst => false,
+            AggregatePolicy::Quorum(quorum) => up_children * 100 >= (*quorum as usize) * children,
+            AggregatePolicy::Minimum(minimum) => healthy_children >= (*minimum as usize),
+        };
+
+        if has_enough_up {
+            Status::Sick
+        } else {
+            Status::Dead
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_worst_status_without_dead_children() {
+        let policy = AggregatePolicy::Quorum(50);
+
+        assert_eq!(policy.resolve(Status::Healthy, 3, 0, 3), Status::Healthy);
+        assert_eq!(policy.resolve(Status::Sick, 3, 0, 1), Status::Sick);
+    }
+
+    #[test]
+    fn it_resolves_worst_as_dead() {
+        assert_eq!(
+            AggregatePolicy::Worst.resolve(Status::Dead, 4, 1, 3),
+            Status::Dead
+        );
+    }
+
+    #[test]
+    fn it_resolves_quorum_with_sick_children_as_up() {
+        let policy = AggregatePolicy::Quorum(50);
+
+        // 2 sick children out of 4 still reach the quorum
+        assert_eq!(policy.resolve(Status::Dead, 4, 2, 0), Status::Sick);
+
+        // 1 sick child out of 4 does not reach the quorum
+        assert_eq!(policy.resolve(Status::Dead, 4, 3, 0), Status::Dead);
+    }
+
+    #[test]
+    fn it_resolves_minimum_with_healthy_children_only() {
+        let policy = AggregatePolicy::Minimum(2);
+
+        // Sick children do not count towards the minimum
+        assert_eq!(policy.resolve(Status::Dead, 4, 1, 1), Status::Dead);
+        assert_eq!(policy.resolve(Status::Dead, 4, 1, 2), Status::Sick);
+    }
+
+    #[test]
+    fn it_validates_policy_from_config() {
+        assert!(AggregatePolicy::from_config(Aggregate::Quorum, Some(0), None).is_err());
+        assert!(AggregatePolicy::from_config(Aggregate::Quorum, Some(101), None).is_err());
+        assert!(AggregatePolicy::from_config(Aggregate::Minimum, None, Some(0)).is_err());
+        assert!(AggregatePolicy::from_config(Aggregate::Minimum, None, None).is_err());
+
+        assert!(matches!(
+            AggregatePolicy::from_config(Aggregate::Quorum, Some(60), None),
+            Ok(AggregatePolicy::Quorum(60))
+        ));
+    }
+}
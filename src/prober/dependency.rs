@@ -0,0 +1,102 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::{HashMap, HashSet};
+
+type DependencyNodeKey<'a> = (&'a str, &'a str);
+
+pub struct DependencyService<'a> {
+    pub id: &'a str,
+    pub depends_on: Vec<&'a str>,
+    pub nodes: Vec<DependencyNode<'a>>,
+}
+
+pub struct DependencyNode<'a> {
+    pub id: &'a str,
+    pub depends_on: Vec<&'a str>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DependencyError<'a> {
+    Unknown(&'a str, &'a str),
+    Cycle(&'a str, &'a str),
+}
+
+pub fn validate<'a>(services: &[DependencyService<'a>]) -> Result<(), DependencyError<'a>> {
+    // Map each node to the nodes it depends on (dependencies on a service are expanded to all \
+    //   nodes of the service)
+    let mut dependencies: HashMap<DependencyNodeKey, Vec<DependencyNodeKey>> = HashMap::new();
+
+    for service in services.iter() {
+        for node in service.nodes.iter() {
+            let mut node_dependencies = Vec::new();
+
+            for reference in service.depends_on.iter().chain(node.depends_on.iter()) {
+                let mut reference_parts = reference.splitn(2, ':');
+
+                let (dependency_service_id, dependency_node_id) =
+                    (reference_parts.next(), reference_parts.next());
+
+                let dependency_service = services
+                    .iter()
+                    .find(|service| Some(service.id) == dependency_service_id)
+                    .ok_or(DependencyError::Unknown(reference, service.id))?;
+
+                let node_dependencies_count = node_dependencies.len();
+
+                for dependency_node in dependency_service.nodes.iter() {
+                    if dependency_node_id.is_none()
+                        || dependency_node_id == Some(dependency_node.id)
+                    {
+                        node_dependencies.push((dependency_service.id, dependency_node.id));
+                    }
+                }
+
+                // Referenced node does not exist in service?
+                if node_dependencies.len() == node_dependencies_count {
+                    return Err(DependencyError::Unknown(reference, service.id));
+                }
+            }
+
+            dependencies.insert((service.id, node.id), node_dependencies);
+        }
+    }
+
+    // Scan for dependency cycles (including nodes depending on themselves)
+    let mut checked_nodes = HashSet::new();
+
+    for node in dependencies.keys() {
+        validate_acyclic(&dependencies, *node, &mut Vec::new(), &mut checked_nodes)?;
+    }
+
+    Ok(())
+}
+
+fn validate_acyclic<'a>(
+    dependencies: &HashMap<DependencyNodeKey<'a>, Vec<DependencyNodeKey<'a>>>,
+    node: DependencyNodeKey<'a>,
+    path: &mut Vec<DependencyNodeKey<'a>>,
+    checked_nodes: &mut HashSet<DependencyNodeKey<'a>>,
+) -> Result<(), DependencyError<'a>> {
+    // Node is already on the current dependency path? (caught a cycle)
+    if path.contains(&node) {
+        return Err(DependencyError::Cycle(node.0, node.1));
+    }
+
+    if !checked_nodes.contains(&node) {
+        path.push(node);
+
+        for dependency in dependencies.get(&node).into_iter().flatten() {
+            validate_acyclic(dependencies, *dependency, path, checked_nodes)?;
+        }
+
+        path.pop();
+
+        checked_nodes.insert(node);
+    }
+
+    Ok(())
+}
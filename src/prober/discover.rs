@@ -0,0 +1,324 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use hickory_resolver::Resolver;
+
+use super::registry::make_replica;
+use crate::prober::manager::STORE as PROBER_STORE;
+use crate::APP_CONF;
+
+const DISCOVER_TEMPLATE_DNS_SRV: &str = "tcp://{host}:{port}";
+const DISCOVER_TEMPLATE_DNS_HOST: &str = "icmp://{host}";
+
+#[derive(Serialize, Clone, Debug)]
+pub enum DiscoverSource {
+    #[serde(rename = "dns_srv")]
+    DnsSrv(String),
+
+    #[serde(rename = "dns_host")]
+    DnsHost(String),
+
+    #[serde(rename = "file")]
+    File(PathBuf),
+}
+
+struct DiscoverTarget {
+    probe_id: String,
+    node_id: String,
+    source: DiscoverSource,
+    template: Option<String>,
+    modified: Option<SystemTime>,
+}
+
+#[derive(Deserialize)]
+struct DiscoverFile {
+    replicas: Vec<String>,
+}
+
+pub struct DiscoverEndpoint {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl DiscoverSource {
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            DiscoverSource::DnsSrv(_) => DISCOVER_TEMPLATE_DNS_SRV,
+            DiscoverSource::DnsHost(_) => DISCOVER_TEMPLATE_DNS_HOST,
+            DiscoverSource::File(_) => "",
+        }
+    }
+}
+
+impl DiscoverEndpoint {
+    pub fn expand(&self, template: &str) -> String {
+        // Notice: the port is not known for all discovery sources, in which case the template \
+        //   placeholder is left as-is (thus the replica URL will not be valid).
+        let replica = template.replace("{host}", &self.host);
+
+        match self.port {
+            Some(port) => replica.replace("{port}", &port.to_string()),
+            None => replica,
+        }
+    }
+}
+
+pub fn run() {
+    loop {
+        debug!("running a discover operation...");
+
+        dispatch_discovers();
+
+        info!("ran discover operation");
+
+        // Hold for next discover run
+        thread::sleep(Duration::from_secs(APP_CONF.metrics.discover_interval));
+    }
+}
+
+fn map_discover_targets() -> Vec<DiscoverTarget> {
+    let mut target_list = Vec::new();
+
+    // Acquire states
+    let states = &PROBER_STORE.read().unwrap().states;
+
+    // Map nodes with a discovery source (clone values, so that the read lock is not held while \
+    //   discovering replicas, as this can take time)
+    for (probe_id, probe) in states.probes.iter() {
+        for (node_id, node) in probe.nodes.iter() {
+            if let Some(ref discover) = node.discover {
+                target_list.push(DiscoverTarget {
+                    probe_id: probe_id.to_owned(),
+                    node_id: node_id.to_owned(),
+                    source: discover.source.to_owned(),
+                    template: discover.template.to_owned(),
+                    modified: discover.modified,
+                });
+            }
+        }
+    }
+
+    target_list
+}
+
+fn dispatch_discovers() {
+    let targets = map_discover_targets();
+
+    // Notice: the DNS resolver is only initialized if there are DNS records to discover from, \
+    //   as it reads the system configuration (eg. '/etc/resolv.conf').
+    let has_dns_targets = targets
+        .iter()
+        .any(|target| !matches!(target.source, DiscoverSource::File(_)));
+
+    let resolver = if has_dns_targets {
+        Some(Resolver::from_system_conf().map_err(|err| {
+            error!("could not initialize discover dns resolver: {}", err);
+
+            err.to_string()
+        }))
+    } else {
+        None
+    };
+
+    for target in targets {
+        let discovered = match (&target.source, &resolver) {
+            (DiscoverSource::DnsSrv(ref name), Some(resolver)) => resolver
+                .as_ref()
+                .map_err(|err| err.to_owned())
+                .and_then(|resolver| proceed_discover_dns_srv(resolver, name))
+                .map(|endpoints| Some((expand_endpoints(&target, endpoints), None))),
+            (DiscoverSource::DnsHost(ref name), Some(resolver)) => resolver
+                .as_ref()
+                .map_err(|err| err.to_owned())
+                .and_then(|resolver| proceed_discover_dns_host(resolver, name))
+                .map(|endpoints| Some((expand_endpoints(&target, endpoints), None))),
+            (DiscoverSource::File(ref path), _) => proceed_discover_file(path, target.modified)
+                .map(|replicas| replicas.map(|(replicas, modified)| (replicas, Some(modified)))),
+            _ => Ok(None),
+        };
+
+        match discovered {
+            Ok(Some((replicas, modified))) => {
+                apply_replicas(&target.probe_id, &target.node_id, replicas, modified);
+            }
+            Ok(None) => {
+                debug!(
+                    "discovered replicas did not change for node: {}:{}",
+                    target.probe_id, target.node_id
+                );
+            }
+            Err(err) => {
+                // Notice: replicas are kept as-is if discovery fails, as this is most likely a \
+                //   temporary failure (dropping all replicas would hide their failures).
+                warn!(
+                    "could not discover replicas for node: {}:{} ({})",
+                    target.probe_id, target.node_id, err
+                );
+            }
+        }
+    }
+}
+
+fn expand_endpoints(target: &DiscoverTarget, endpoints: Vec<DiscoverEndpoint>) -> Vec<String> {
+    let template = target
+        .template
+        .as_deref()
+        .unwrap_or_else(|| target.source.default_template());
+
+    endpoints
+        .iter()
+        .map(|endpoint| endpoint.expand(template))
+        .collect()
+}
+
+fn proceed_discover_dns_srv(
+    resolver: &Resolver,
+    name: &str,
+) -> Result<Vec<DiscoverEndpoint>, String> {
+    debug!("will discover replicas from dns srv record: {}", name);
+
+    let lookup = resolver.srv_lookup(name).map_err(|err| err.to_string())?;
+
+    Ok(lookup
+        .iter()
+        .map(|record| DiscoverEndpoint {
+            host: record.target().to_utf8().trim_end_matches('.').to_string(),
+            port: Some(record.port()),
+        })
+        .collect())
+}
+
+fn proceed_discover_dns_host(
+    resolver: &Resolver,
+    name: &str,
+) -> Result<Vec<DiscoverEndpoint>, String> {
+    debug!("will discover replicas from dns host record: {}", name);
+
+    let lookup = resolver.lookup_ip(name).map_err(|err| err.to_string())?;
+
+    Ok(lookup
+        .iter()
+        .map(|address| DiscoverEndpoint {
+            // IPv6 addresses must be enclosed in brackets, as they are used in URLs
+            host: match address {
+                IpAddr::V4(address) => address.to_string(),
+                IpAddr::V6(address) => format!("[{}]", address),
+            },
+            port: None,
+        })
+        .collect())
+}
+
+fn proceed_discover_file(
+    path: &Path,
+    modified: Option<SystemTime>,
+) -> Result<Option<(Vec<String>, SystemTime)>, String> {
+    let file_modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|err| err.to_string())?;
+
+    // File did not change since it was last read? (skip it)
+    if modified == Some(file_modified) {
+        return Ok(None);
+    }
+
+    debug!("will discover replicas from file: {:?}", path);
+
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    // Parse file from its extension (JSON and TOML files are supported)
+    let file: DiscoverFile = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&contents).map_err(|err| err.to_string())?,
+        Some("toml") => toml::from_str(&contents).map_err(|err| err.to_string())?,
+        _ => return Err("unsupported file extension (use .json or .toml)".to_string()),
+    };
+
+    Ok(Some((file.replicas, file_modified)))
+}
+
+fn apply_replicas(
+    probe_id: &str,
+    node_id: &str,
+    replicas: Vec<String>,
+    modified: Option<SystemTime>,
+) {
+    // Update node replicas (write-lock the store)
+    let mut store = PROBER_STORE.write().unwrap();
+
+    let probe_node = match store
+        .states
+        .probes
+        .get_mut(probe_id)
+        .and_then(|probe| probe.nodes.get_mut(node_id))
+    {
+        Some(probe_node) => probe_node,
+        None => return,
+    };
+
+    let previous_replicas = match probe_node.discover {
+        Some(ref mut discover) => std::mem::take(&mut discover.replicas),
+        None => return,
+    };
+
+    let mut discovered_replicas = Vec::new();
+
+    for replica_id in replicas {
+        if discovered_replicas.contains(&replica_id) {
+            continue;
+        }
+
+        // Notice: replicas that were not added by discovery (eg. set in configuration) are left \
+        //   untouched, and replicas that were already discovered keep their current status.
+        if previous_replicas.contains(&replica_id) {
+            discovered_replicas.push(replica_id);
+        } else if !probe_node.replicas.contains_key(&replica_id) {
+            match make_replica(probe_node, Some(&replica_id), None) {
+                Ok((replica_id, replica)) => {
+                    info!(
+                        "discovered new replica: {}:{}:{}",
+                        probe_id, node_id, replica_id
+                    );
+
+                    probe_node.replicas.insert(replica_id.to_owned(), replica);
+
+                    discovered_replicas.push(replica_id);
+                }
+                Err(err) => {
+                    warn!(
+                        "discovered invalid replica: {}:{}:{} ({})",
+                        probe_id,
+                        node_id,
+                        replica_id,
+                        err.as_str()
+                    );
+                }
+            }
+        }
+    }
+
+    // Drop vanished replicas
+    for replica_id in previous_replicas {
+        if !discovered_replicas.contains(&replica_id) {
+            info!(
+                "dropped vanished replica: {}:{}:{}",
+                probe_id, node_id, replica_id
+            );
+
+            probe_node.replicas.shift_remove(&replica_id);
+        }
+    }
+
+    if let Some(ref mut discover) = probe_node.discover {
+        discover.replicas = discovered_replicas;
+        discover.modified = modified;
+    }
+}
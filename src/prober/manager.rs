@@ -5,7 +5,10 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 use std::cmp::min;
-use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::thread;
@@ -13,30 +16,56 @@ use std::time::{Duration, SystemTime};
 use time;
 
 use indexmap::IndexMap;
+use native_tls::TlsConnector;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use ping::ping;
-use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, USER_AGENT};
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{HeaderMap, HeaderValue, HOST, USER_AGENT};
 use reqwest::redirect::Policy as RedirectPolicy;
-use reqwest::StatusCode;
+use reqwest::{Certificate, Identity, StatusCode};
 use run_script::{self, ScriptOptions};
+use serde_json::Value as JSONValue;
 
 use ssh2::Session;
-
+use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+use tonic::transport::{ClientTlsConfig, Endpoint};
+use tonic::Code as GRPCCode;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::{client_tls_with_config, Message as WebSocketMessage};
+use url::{Host, Url};
+
+use super::registry::{label_dependencies, make_probe};
 use super::replica::ReplicaURL;
 use super::states::{
-    ServiceStates, ServiceStatesNotifier, ServiceStatesProbe, ServiceStatesProbeNode,
-    ServiceStatesProbeNodeRabbitMQ, ServiceStatesProbeNodeReplica,
-    ServiceStatesProbeNodeReplicaMetrics, ServiceStatesProbeNodeReplicaMetricsRabbitMQ,
+    ServiceStates, ServiceStatesNotifier, ServiceStatesProbeNodeHTTPClient,
+    ServiceStatesProbeNodeRabbitMQ, ServiceStatesProbeNodeReplicaMetricsRabbitMQ,
+    ServiceStatesProbeNodeTCP,
 };
 use super::status::Status;
-use crate::config::config::{ConfigPluginsRabbitMQ, ConfigProbeServiceNodeHTTPMethod};
+use crate::config::config::{
+    ConfigPluginsRabbitMQ, ConfigProbeServiceNodeHTTPAssert, ConfigProbeServiceNodeHTTPIPVersion,
+    ConfigProbeServiceNodeHTTPMethod, ConfigProbeServiceNodeHTTPStep,
+    ConfigProbeServiceNodeHTTPStepExtract, ConfigProbeServiceNodePayloadEncoding,
+    ConfigProbeServiceNodeTCPTLS,
+};
 use crate::config::regex::Regex;
 use crate::prober::manager::STORE as PROBER_STORE;
 use crate::prober::mode::Mode;
 use crate::APP_CONF;
+use crate::THREAD_NAME_PROBER_GRPC;
 
 const PROBE_ICMP_TIMEOUT_SECONDS: u64 = 1;
+const PROBE_UDP_UNREACHABLE_WAIT_SECONDS: u64 = 1;
 const SECOND_TO_MILLISECONDS: u32 = 1000;
+const PROBE_TCP_REPLY_SIZE_MAXIMUM: usize = 65536;
+const PROBE_HTTP_URL_VARIABLE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
 
 lazy_static! {
     pub static ref STORE: Arc<RwLock<Store>> = Arc::new(RwLock::new(Store {
@@ -52,11 +81,14 @@ lazy_static! {
         },
         notified: None,
     }));
-    static ref PROBE_HTTP_CLIENT: Client = Client::builder()
-        .timeout(Duration::from_secs(APP_CONF.metrics.poll_delay_dead))
-        .gzip(false)
-        .redirect(RedirectPolicy::none())
-        .default_headers(make_default_headers())
+    static ref PROBE_HTTP_CLIENT: Client = make_http_client(None, None).unwrap();
+
+    // Notice: the gRPC client is asynchronous, thus a runtime is shared by all prober threads \
+    //   to run health check calls. Its single worker drives connections in the background.
+    static ref PROBE_GRPC_RUNTIME: Runtime = RuntimeBuilder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name(THREAD_NAME_PROBER_GRPC)
+        .enable_all()
         .build()
         .unwrap();
 }
@@ -86,8 +118,21 @@ struct ProbeReplicaPoll {
     pub http_method: Option<ConfigProbeServiceNodeHTTPMethod>,
     pub http_body: Option<String>,
     pub body_match: Option<Regex>,
+    pub http_assert: Vec<ConfigProbeServiceNodeHTTPAssert>,
+    pub http_steps: Vec<ConfigProbeServiceNodeHTTPStep>,
+    pub http_client: Option<ServiceStatesProbeNodeHTTPClient>,
+    pub websocket_message: Option<String>,
+    pub websocket_reply_healthy_match: Option<Regex>,
+    pub udp_payload: Option<Vec<u8>>,
+    pub udp_payload_encoding: ConfigProbeServiceNodePayloadEncoding,
+    pub udp_reply_healthy_match: Option<Regex>,
+    pub tcp: Option<ServiceStatesProbeNodeTCP>,
 }
 
+trait ProbeStream: Read + Write {}
+
+impl<T: Read + Write> ProbeStream for T {}
+
 #[derive(Clone)]
 struct ProbeReplicaScript {
     pub script: String,
@@ -95,10 +140,72 @@ struct ProbeReplicaScript {
 
 #[derive(Clone)]
 enum ProbeReplica {
-    Poll(ProbeReplicaTarget, ProbeReplicaPoll),
+    Poll(ProbeReplicaTarget, Box<ProbeReplicaPoll>),
     Script(ProbeReplicaTarget, ProbeReplicaScript),
 }
 
+pub fn make_http_client(
+    http_client: Option<&ServiceStatesProbeNodeHTTPClient>,
+    resolve: Option<(&str, &[SocketAddr])>,
+) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(APP_CONF.metrics.poll_delay_dead))
+        .gzip(false)
+        .redirect(RedirectPolicy::none())
+        .default_headers(make_default_headers());
+
+    // Apply node HTTP client options? (if any)
+    if let Some(http_client) = http_client {
+        if http_client.redirects > 0 {
+            builder = builder.redirect(RedirectPolicy::limited(http_client.redirects));
+        }
+
+        if let Some(ref tls_ca) = http_client.tls_ca {
+            let certificates = fs::read(tls_ca)
+                .map_err(|err| err.to_string())
+                .and_then(|pem| {
+                    Certificate::from_pem_bundle(&pem).map_err(|err| err.to_string())
+                })?;
+
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let (Some(ref tls_cert), Some(ref tls_key)) =
+            (&http_client.tls_cert, &http_client.tls_key)
+        {
+            let (cert, key) = (
+                fs::read(tls_cert).map_err(|err| err.to_string())?,
+                fs::read(tls_key).map_err(|err| err.to_string())?,
+            );
+
+            builder = builder
+                .identity(Identity::from_pkcs8_pem(&cert, &key).map_err(|err| err.to_string())?);
+        }
+
+        // Notice: forcing an IP version is done by binding to the unspecified address of this \
+        //   version, so that connections to addresses of the other version cannot be made.
+        builder = match http_client.ip_version {
+            Some(ConfigProbeServiceNodeHTTPIPVersion::IPv4) => {
+                builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+            }
+            Some(ConfigProbeServiceNodeHTTPIPVersion::IPv6) => {
+                builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+            }
+            None => builder,
+        };
+
+        builder = builder.danger_accept_invalid_certs(http_client.tls_insecure);
+    }
+
+    if let Some((domain, addresses)) = resolve {
+        builder = builder.resolve_to_addrs(domain, addresses);
+    }
+
+    builder.build().map_err(|err| err.to_string())
+}
+
 fn make_default_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
 
@@ -134,13 +241,24 @@ fn map_poll_replicas() -> Vec<ProbeReplica> {
                                 node_id: node_id.to_owned(),
                                 replica_id: replica_id.to_owned(),
                             },
-                            ProbeReplicaPoll {
+                            Box::new(ProbeReplicaPoll {
                                 replica_url: replica_url.to_owned(),
                                 http_headers: node.http_headers.to_owned(),
                                 http_method: node.http_method.to_owned(),
                                 http_body: node.http_body.to_owned(),
                                 body_match: node.http_body_healthy_match.to_owned(),
-                            },
+                                http_assert: node.http_assert.to_owned(),
+                                http_steps: node.http_steps.to_owned(),
+                                http_client: node.http_client.to_owned(),
+                                websocket_message: node.websocket_message.to_owned(),
+                                websocket_reply_healthy_match: node
+                                    .websocket_reply_healthy_match
+                                    .to_owned(),
+                                udp_payload: node.udp_payload.to_owned(),
+                                udp_payload_encoding: node.udp_payload_encoding,
+                                udp_reply_healthy_match: node.udp_reply_healthy_match.to_owned(),
+                                tcp: node.tcp.to_owned(),
+                            }),
                         ));
                     }
                 }
@@ -185,29 +303,19 @@ fn map_script_replicas() -> Vec<ProbeReplica> {
 }
 
 fn proceed_replica_probe_poll_with_retry(
-    replica_url: &ReplicaURL,
-    http_headers: &HeaderMap,
-    http_method: &Option<ConfigProbeServiceNodeHTTPMethod>,
-    http_body: &Option<String>,
-    body_match: &Option<Regex>,
+    probe_replica_poll: &ProbeReplicaPoll,
 ) -> (Status, Option<Duration>) {
     let (mut status, mut latency, mut retry_count) = (Status::Dead, None, 0);
 
     while retry_count <= APP_CONF.metrics.poll_retry && status == Status::Dead {
         debug!(
             "will probe replica: {:?} with retry count: {} (after {}ms)",
-            replica_url, retry_count, APP_CONF.metrics.poll_retry_wait
+            probe_replica_poll.replica_url, retry_count, APP_CONF.metrics.poll_retry_wait
         );
 
         thread::sleep(Duration::from_millis(APP_CONF.metrics.poll_retry_wait));
 
-        let probe_results = proceed_replica_probe_poll(
-            replica_url,
-            http_headers,
-            http_method,
-            http_body,
-            body_match,
-        );
+        let probe_results = proceed_replica_probe_poll(probe_replica_poll);
 
         status = probe_results.0;
         latency = Some(probe_results.1);
@@ -219,21 +327,35 @@ fn proceed_replica_probe_poll_with_retry(
     (status, latency)
 }
 
-fn proceed_replica_probe_poll(
-    replica_url: &ReplicaURL,
-    http_headers: &HeaderMap,
-    http_method: &Option<ConfigProbeServiceNodeHTTPMethod>,
-    http_body: &Option<String>,
-    body_match: &Option<Regex>,
-) -> (Status, Duration) {
+fn proceed_replica_probe_poll(probe_replica_poll: &ProbeReplicaPoll) -> (Status, Duration) {
     let start_time = SystemTime::now();
 
-    let (is_up, poll_duration) = match replica_url {
-        &ReplicaURL::ICMP(ref host) => proceed_replica_probe_poll_icmp(host),
-        &ReplicaURL::TCP(ref host, port) => proceed_replica_probe_poll_tcp(host, port),
-        &ReplicaURL::SSH(ref host, port) => proceed_replica_probe_poll_ssh(host, port),
+    let (poll_status, poll_duration) = match &probe_replica_poll.replica_url {
+        ReplicaURL::ICMP(host) => map_poll_status(proceed_replica_probe_poll_icmp(host)),
+        &ReplicaURL::TCP(ref host, port) => map_poll_status(proceed_replica_probe_poll_tcp(
+            host,
+            port,
+            probe_replica_poll,
+        )),
+        &ReplicaURL::UDP(ref host, port) => map_poll_status(proceed_replica_probe_poll_udp(
+            host,
+            port,
+            probe_replica_poll,
+        )),
+        &ReplicaURL::SSH(ref host, port) => {
+            map_poll_status(proceed_replica_probe_poll_ssh(host, port))
+        }
         &ReplicaURL::HTTP(ref url) | &ReplicaURL::HTTPS(ref url) => {
-            proceed_replica_probe_poll_http(url, http_headers, http_method, http_body, body_match)
+            proceed_replica_probe_poll_http(url, probe_replica_poll)
+        }
+        &ReplicaURL::WS(ref url) | &ReplicaURL::WSS(ref url) => map_poll_status(
+            proceed_replica_probe_poll_websocket(url, probe_replica_poll),
+        ),
+        &ReplicaURL::GRPC(ref host, port, ref service) => {
+            proceed_replica_probe_poll_grpc(host, port, service, false)
+        }
+        &ReplicaURL::GRPCS(ref host, port, ref service) => {
+            proceed_replica_probe_poll_grpc(host, port, service, true)
         }
     };
 
@@ -244,16 +366,21 @@ fn proceed_replica_probe_poll(
             .unwrap_or(Duration::from_secs(0)),
     };
 
-    if is_up == true {
-        // Probe reports as sick?
-        if duration_latency >= Duration::from_secs(APP_CONF.metrics.poll_delay_sick) {
-            return (Status::Sick, duration_latency);
-        }
-
-        (Status::Healthy, duration_latency)
-    } else {
-        (Status::Dead, duration_latency)
+    // Probe reports as sick? (if it was otherwise healthy)
+    if poll_status == Status::Healthy
+        && duration_latency >= Duration::from_secs(APP_CONF.metrics.poll_delay_sick)
+    {
+        return (Status::Sick, duration_latency);
     }
+
+    (poll_status, duration_latency)
+}
+
+fn map_poll_status((is_up, poll_duration): (bool, Option<Duration>)) -> (Status, Option<Duration>) {
+    (
+        if is_up { Status::Healthy } else { Status::Dead },
+        poll_duration,
+    )
 }
 
 fn proceed_replica_probe_poll_icmp(host: &str) -> (bool, Option<Duration>) {
@@ -364,7 +491,11 @@ fn proceed_replica_probe_poll_icmp(host: &str) -> (bool, Option<Duration>) {
     (true, maximum_rtt)
 }
 
-fn proceed_replica_probe_poll_tcp(host: &str, port: u16) -> (bool, Option<Duration>) {
+fn proceed_replica_probe_poll_tcp(
+    host: &str,
+    port: u16,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> (bool, Option<Duration>) {
     let address_results = (host, port).to_socket_addrs();
 
     match address_results {
@@ -375,12 +506,23 @@ fn proceed_replica_probe_poll_tcp(host: &str, port: u16) -> (bool, Option<Durati
                 return match TcpStream::connect_timeout(
                     &address_value,
                     Duration::from_secs(APP_CONF.metrics.poll_delay_dead),
-                ) {
-                    Ok(_) => {
+                )
+                .and_then(|stream| {
+                    proceed_replica_probe_poll_tcp_exchange(host, stream, probe_replica_poll)
+                }) {
+                    Ok(true) => {
                         debug!("prober poll success for tcp target: {}", address_value);
 
                         (true, None)
                     }
+                    Ok(false) => {
+                        debug!(
+                            "prober poll did not receive a matching reply for tcp target: {}",
+                            address_value
+                        );
+
+                        (false, None)
+                    }
                     Err(err) => {
                         debug!(
                             "prober poll error for tcp target: {} (error: {})",
@@ -408,6 +550,314 @@ fn proceed_replica_probe_poll_tcp(host: &str, port: u16) -> (bool, Option<Durati
     (false, None)
 }
 
+fn proceed_replica_probe_poll_tcp_exchange(
+    host: &str,
+    stream: TcpStream,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> Result<bool, io::Error> {
+    // Only check that the connection opens? (no TCP option set)
+    let tcp = match probe_replica_poll.tcp {
+        Some(ref tcp) => tcp,
+        None => return Ok(true),
+    };
+
+    let start_time = SystemTime::now();
+
+    // Notice: a handle on the underlying socket is kept, so that read timeouts can be updated \
+    //   while waiting for replies (as the socket may get moved to a TLS stream).
+    let socket = stream.try_clone()?;
+
+    socket.set_write_timeout(Some(Duration::from_secs(APP_CONF.metrics.poll_delay_dead)))?;
+
+    // Wrap connection in TLS? (either right away, or after a STARTTLS exchange, in which case \
+    //   the banner is expected before the exchange)
+    let mut stream: Box<dyn ProbeStream> = match tcp.tls {
+        None => Box::new(stream),
+        Some(ConfigProbeServiceNodeTCPTLS::TLS) => {
+            // Bound the TLS handshake (which reads from the socket)
+            if !set_tcp_read_timeout(&socket, start_time)? {
+                return Ok(false);
+            }
+
+            make_tcp_tls_stream(host, stream, tcp.tls_insecure)?
+        }
+        Some(ConfigProbeServiceNodeTCPTLS::StartTLS) => {
+            let mut stream = stream;
+
+            if let Some(ref banner_match) = tcp.banner_healthy_match {
+                if !expect_tcp_reply(&mut stream, &socket, start_time, banner_match, None)? {
+                    return Ok(false);
+                }
+            }
+
+            for step in &tcp.starttls {
+                stream.write_all(step.send.as_bytes())?;
+
+                if !expect_tcp_reply(&mut stream, &socket, start_time, &step.expect, None)? {
+                    return Ok(false);
+                }
+            }
+
+            // Bound the TLS handshake (which reads from the socket)
+            if !set_tcp_read_timeout(&socket, start_time)? {
+                return Ok(false);
+            }
+
+            make_tcp_tls_stream(host, stream, tcp.tls_insecure)?
+        }
+    };
+
+    // Expect banner? (if not already expected)
+    if let Some(ref banner_match) = tcp.banner_healthy_match {
+        if tcp.tls != Some(ConfigProbeServiceNodeTCPTLS::StartTLS)
+            && !expect_tcp_reply(&mut stream, &socket, start_time, banner_match, None)?
+        {
+            return Ok(false);
+        }
+    }
+
+    // Send payload? (if configured)
+    if let Some(ref payload) = tcp.payload {
+        stream.write_all(payload)?;
+    }
+
+    // Expect a matching reply? (if configured, matched in the same encoding as the payload)
+    if let Some(ref reply_match) = tcp.reply_healthy_match {
+        return expect_tcp_reply(
+            &mut stream,
+            &socket,
+            start_time,
+            reply_match,
+            Some(tcp.payload_encoding),
+        );
+    }
+
+    Ok(true)
+}
+
+fn make_tcp_tls_stream(
+    host: &str,
+    stream: TcpStream,
+    tls_insecure: bool,
+) -> Result<Box<dyn ProbeStream>, io::Error> {
+    let connector = TlsConnector::builder()
+        .danger_accept_invalid_certs(tls_insecure)
+        .build()
+        .map_err(io::Error::other)?;
+
+    let stream = connector
+        .connect(host, stream)
+        .map_err(|err| io::Error::other(err.to_string()))?;
+
+    Ok(Box::new(stream))
+}
+
+fn set_tcp_read_timeout(socket: &TcpStream, start_time: SystemTime) -> Result<bool, io::Error> {
+    let timeout = Duration::from_secs(APP_CONF.metrics.poll_delay_dead);
+
+    let elapsed = SystemTime::now()
+        .duration_since(start_time)
+        .unwrap_or(Duration::from_secs(0));
+
+    // Exchange timed out? (no time left to read)
+    if elapsed >= timeout {
+        return Ok(false);
+    }
+
+    socket.set_read_timeout(Some(timeout - elapsed))?;
+
+    Ok(true)
+}
+
+fn expect_tcp_reply(
+    stream: &mut dyn ProbeStream,
+    socket: &TcpStream,
+    start_time: SystemTime,
+    reply_match: &Regex,
+    encoding: Option<ConfigProbeServiceNodePayloadEncoding>,
+) -> Result<bool, io::Error> {
+    let (mut received, mut buffer) = (Vec::new(), [0; 4096]);
+
+    // Read until the reply matches (replies may be received in multiple parts), the connection \
+    //   gets closed or the timeout is reached
+    while received.len() < PROBE_TCP_REPLY_SIZE_MAXIMUM {
+        if !set_tcp_read_timeout(socket, start_time)? {
+            return Ok(false);
+        }
+
+        let size = match stream.read(&mut buffer) {
+            Ok(0) => return Ok(false),
+            Ok(size) => size,
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                return Ok(false)
+            }
+            Err(err) => return Err(err),
+        };
+
+        received.extend_from_slice(&buffer[..size]);
+
+        let reply = encode_reply(
+            &received,
+            encoding.unwrap_or(ConfigProbeServiceNodePayloadEncoding::Text),
+        );
+
+        debug!("checking tcp reply for any match: {}", reply);
+
+        if reply_match.is_match(&reply) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn encode_reply(data: &[u8], encoding: ConfigProbeServiceNodePayloadEncoding) -> String {
+    match encoding {
+        ConfigProbeServiceNodePayloadEncoding::Text => String::from_utf8_lossy(data).to_string(),
+        ConfigProbeServiceNodePayloadEncoding::Hex => {
+            data.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+    }
+}
+
+fn proceed_replica_probe_poll_udp(
+    host: &str,
+    port: u16,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> (bool, Option<Duration>) {
+    let address_results = (host, port).to_socket_addrs();
+
+    match address_results {
+        Ok(mut address) => {
+            if let Some(address_value) = address.next() {
+                debug!("prober poll will fire for udp target: {}", address_value);
+
+                return match proceed_replica_probe_poll_udp_exchange(
+                    &address_value,
+                    probe_replica_poll,
+                ) {
+                    Ok(true) => {
+                        debug!("prober poll success for udp target: {}", address_value);
+
+                        (true, None)
+                    }
+                    Ok(false) => {
+                        debug!(
+                            "prober poll did not receive a matching reply for udp target: {}",
+                            address_value
+                        );
+
+                        (false, None)
+                    }
+                    Err(err) => {
+                        debug!(
+                            "prober poll error for udp target: {} (error: {})",
+                            address_value, err
+                        );
+
+                        (false, None)
+                    }
+                };
+            } else {
+                debug!(
+                    "prober poll did not resolve any address for udp replica: {}:{}",
+                    host, port
+                );
+            }
+        }
+        Err(err) => {
+            error!(
+                "prober poll address for udp replica is invalid: {}:{} (error: {})",
+                host, port, err
+            );
+        }
+    };
+
+    (false, None)
+}
+
+fn proceed_replica_probe_poll_udp_exchange(
+    address: &SocketAddr,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> Result<bool, io::Error> {
+    let start_time = SystemTime::now();
+    let timeout = Duration::from_secs(APP_CONF.metrics.poll_delay_dead);
+
+    // Notice: the socket is connected to the target, so that only its datagrams are received \
+    //   (and so that ICMP 'port unreachable' errors get reported upon receiving).
+    let socket = UdpSocket::bind(if address.is_ipv4() {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)
+    } else {
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)
+    })?;
+
+    socket.connect(address)?;
+    socket.set_write_timeout(Some(timeout))?;
+
+    socket.send(
+        probe_replica_poll
+            .udp_payload
+            .as_deref()
+            .unwrap_or_default(),
+    )?;
+
+    let mut buffer = [0; 65536];
+
+    // Expect a matching reply? (if configured, until the timeout is reached)
+    let reply_match = match probe_replica_poll.udp_reply_healthy_match {
+        Some(ref reply_match) => reply_match,
+        None => {
+            // Notice: as UDP is connectionless, the target is only known to be down if an ICMP \
+            //   'port unreachable' error is received back. Any reply, or no reply at all within \
+            //   a short delay, is thus considered healthy.
+            socket.set_read_timeout(Some(min(
+                timeout,
+                Duration::from_secs(PROBE_UDP_UNREACHABLE_WAIT_SECONDS),
+            )))?;
+
+            return match socket.recv(&mut buffer) {
+                Ok(_) => Ok(true),
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut =>
+                {
+                    Ok(true)
+                }
+                Err(err) => Err(err),
+            };
+        }
+    };
+
+    loop {
+        let elapsed = SystemTime::now()
+            .duration_since(start_time)
+            .unwrap_or(Duration::from_secs(0));
+
+        if elapsed >= timeout {
+            return Ok(false);
+        }
+
+        socket.set_read_timeout(Some(timeout - elapsed))?;
+
+        let size = socket.recv(&mut buffer)?;
+
+        // Match reply in the same encoding as the payload
+        let reply = encode_reply(&buffer[..size], probe_replica_poll.udp_payload_encoding);
+
+        debug!(
+            "checking udp reply for udp target: {} for any match: {}",
+            address, reply
+        );
+
+        if reply_match.is_match(&reply) {
+            return Ok(true);
+        }
+    }
+}
+
 fn proceed_replica_probe_poll_ssh(host: &str, port: u16) -> (bool, Option<Duration>) {
     let address_results = (host, port).to_socket_addrs();
 
@@ -471,26 +921,287 @@ fn proceed_replica_probe_poll_ssh(host: &str, port: u16) -> (bool, Option<Durati
     (false, None)
 }
 
-fn proceed_replica_probe_poll_http(
+fn proceed_replica_probe_poll_websocket(
     url: &str,
-    http_headers: &HeaderMap,
-    http_method: &Option<ConfigProbeServiceNodeHTTPMethod>,
-    http_body: &Option<String>,
-    body_match: &Option<Regex>,
+    probe_replica_poll: &ProbeReplicaPoll,
 ) -> (bool, Option<Duration>) {
-    // Acquire query string separator (if the URL already contains a query string, use append mode)
-    let query_separator = if url.contains("?") { "&" } else { "?" };
+    let start_time = SystemTime::now();
+    let timeout = Duration::from_secs(APP_CONF.metrics.poll_delay_dead);
 
-    // Generate URL with cache buster, to bypass any upstream cache (eg. CDN cache layer)
-    let url_bang = format!(
-        "{}{}{}",
-        url,
-        query_separator,
-        time::OffsetDateTime::now_utc().unix_timestamp()
+    debug!("prober poll will fire for websocket target: {}", url);
+
+    // Connect to each resolved address in turn, until one accepts the connection (within the \
+    //   timeout)
+    let stream = Url::parse(url)
+        .map_err(|err| err.to_string())
+        .and_then(|url| resolve_url_addresses(&url))
+        .and_then(|addresses| {
+            let mut last_error = "no address resolved".to_string();
+
+            for address in addresses {
+                let elapsed = SystemTime::now()
+                    .duration_since(start_time)
+                    .unwrap_or(Duration::from_secs(0));
+
+                if elapsed >= timeout {
+                    break;
+                }
+
+                match TcpStream::connect_timeout(&address, timeout - elapsed) {
+                    Ok(stream) => return Ok(stream),
+                    Err(err) => {
+                        debug!(
+                            "could not connect to websocket target: {} at address: {} (error: {})",
+                            url, address, err
+                        );
+
+                        last_error = err.to_string();
+                    }
+                }
+            }
+
+            Err(last_error)
+        });
+
+    let stream = match stream {
+        Ok(stream) => stream,
+        Err(err) => {
+            debug!(
+                "could not connect to websocket target: {} (error: {})",
+                url, err
+            );
+
+            return (false, None);
+        }
+    };
+
+    // Notice: a handle on the underlying socket is kept, so that read timeouts can be updated \
+    //   while waiting for a reply (as the socket gets moved to the WebSocket client).
+    let socket = match stream.try_clone() {
+        Ok(socket) => socket,
+        Err(_) => return (false, None),
+    };
+
+    if socket.set_read_timeout(Some(timeout)).is_err()
+        || socket.set_write_timeout(Some(timeout)).is_err()
+    {
+        return (false, None);
+    }
+
+    // Build handshake request (with configured HTTP headers, if any)
+    let mut request = match url.into_client_request() {
+        Ok(request) => request,
+        Err(_) => return (false, None),
+    };
+
+    request.headers_mut().extend(
+        make_default_headers()
+            .into_iter()
+            .chain(probe_replica_poll.http_headers.to_owned()),
     );
 
+    let mut websocket = match client_tls_with_config(request, stream, None, None) {
+        Ok((websocket, _)) => websocket,
+        Err(err) => {
+            debug!(
+                "websocket handshake failed for websocket target: {} (error: {})",
+                url, err
+            );
+
+            return (false, None);
+        }
+    };
+
+    debug!(
+        "websocket handshake succeeded for websocket target: {}",
+        url
+    );
+
+    // Send message? (if configured)
+    if let Some(ref message) = probe_replica_poll.websocket_message {
+        if websocket
+            .send(WebSocketMessage::text(message.to_owned()))
+            .is_err()
+        {
+            return (false, None);
+        }
+    }
+
+    // Expect a matching reply? (if configured, until the timeout is reached)
+    let is_up = match probe_replica_poll.websocket_reply_healthy_match {
+        Some(ref reply_match) => loop {
+            let elapsed = SystemTime::now()
+                .duration_since(start_time)
+                .unwrap_or(Duration::from_secs(0));
+
+            if elapsed >= timeout || socket.set_read_timeout(Some(timeout - elapsed)).is_err() {
+                break false;
+            }
+
+            match websocket.read() {
+                Ok(WebSocketMessage::Text(text)) => {
+                    debug!(
+                        "checking websocket reply for websocket target: {} for any match: {}",
+                        url,
+                        text.as_str()
+                    );
+
+                    if reply_match.is_match(text.as_str()) {
+                        break true;
+                    }
+                }
+                Ok(WebSocketMessage::Binary(data)) => {
+                    if reply_match.is_match(&String::from_utf8_lossy(&data)) {
+                        break true;
+                    }
+                }
+                Ok(WebSocketMessage::Close(_)) | Err(_) => break false,
+                Ok(_) => {}
+            }
+        },
+        None => true,
+    };
+
+    // Close connection (ignore errors, as the probe result is known already)
+    let _ = websocket.close(None);
+
+    (is_up, None)
+}
+
+fn proceed_replica_probe_poll_grpc(
+    host: &str,
+    port: u16,
+    service: &Option<String>,
+    tls: bool,
+) -> (Status, Option<Duration>) {
+    // IPv6 addresses must be enclosed in brackets, as they are used in URIs
+    let uri = format!(
+        "{}://{}:{}",
+        if tls { "https" } else { "http" },
+        if host.contains(':') {
+            format!("[{}]", host)
+        } else {
+            host.to_string()
+        },
+        port
+    );
+
+    debug!(
+        "prober poll will fire for grpc target: {} with service: {:?}",
+        uri, service
+    );
+
+    let response = PROBE_GRPC_RUNTIME.block_on(async {
+        let mut endpoint = Endpoint::from_shared(uri.to_owned())
+            .map_err(|err| err.to_string())?
+            .connect_timeout(Duration::from_secs(APP_CONF.metrics.poll_delay_dead))
+            .timeout(Duration::from_secs(APP_CONF.metrics.poll_delay_dead));
+
+        if tls {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_native_roots())
+                .map_err(|err| err.to_string())?;
+        }
+
+        let channel = endpoint.connect().await.map_err(|err| err.to_string())?;
+
+        Ok::<_, String>(
+            HealthClient::new(channel)
+                .check(HealthCheckRequest {
+                    service: service.to_owned().unwrap_or_default(),
+                })
+                .await,
+        )
+    });
+
+    match response {
+        Ok(Ok(response_inner)) => {
+            let serving_status = response_inner.into_inner().status();
+
+            debug!(
+                "prober poll result received for grpc target: {} with status: {:?}",
+                uri, serving_status
+            );
+
+            match serving_status {
+                ServingStatus::Serving => (Status::Healthy, None),
+                ServingStatus::NotServing => (Status::Dead, None),
+                ServingStatus::Unknown | ServingStatus::ServiceUnknown => (Status::Sick, None),
+            }
+        }
+        Ok(Err(err)) => {
+            debug!(
+                "prober poll result was not received for grpc target: {} (error: {})",
+                uri, err
+            );
+
+            // Consider as SICK if the server does not know about the service (as it is up), \
+            //   otherwise consider as DOWN.
+            if err.code() == GRPCCode::NotFound {
+                (Status::Sick, None)
+            } else {
+                (Status::Dead, None)
+            }
+        }
+        Err(err) => {
+            debug!("could not connect to grpc target: {} (error: {})", uri, err);
+
+            (Status::Dead, None)
+        }
+    }
+}
+
+fn proceed_replica_probe_poll_http(
+    url: &str,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> (Status, Option<Duration>) {
+    let (http_headers, http_method, http_body, body_match, http_assert) = (
+        &probe_replica_poll.http_headers,
+        &probe_replica_poll.http_method,
+        &probe_replica_poll.http_body,
+        &probe_replica_poll.body_match,
+        &probe_replica_poll.http_assert,
+    );
+
+    // Acquire HTTP client to use for probe query (nodes with HTTP client options hold their own)
+    let (http_client, url, http_host) =
+        match acquire_http_client(url, &probe_replica_poll.http_client) {
+            Ok(http_client) => http_client,
+            Err(err) => {
+                debug!(
+                    "could not acquire http client for http target: {} (error: {})",
+                    url, err
+                );
+
+                return (Status::Dead, None);
+            }
+        };
+
+    // Run HTTP steps? (in place of the single request)
+    if !probe_replica_poll.http_steps.is_empty() {
+        return (
+            proceed_replica_probe_poll_http_steps(
+                &http_client,
+                &url,
+                &http_host,
+                probe_replica_poll,
+            ),
+            None,
+        );
+    }
+
+    let url_bang = make_http_url_bang(&url);
+
+    // Acquire whether the response body needs to be checked (by a matcher or assertions)
+    let check_body = body_match.is_some()
+        || http_assert.iter().any(|http_assert| {
+            http_assert.json_path.is_some()
+                || http_assert.body_size_above.is_some()
+                || http_assert.body_size_below.is_some()
+        });
+
     // Acquire effective HTTP method to use for probe query
-    let effective_http_method = http_method.as_ref().unwrap_or(if body_match.is_some() {
+    let effective_http_method = http_method.as_ref().unwrap_or(if check_body {
         &ConfigProbeServiceNodeHTTPMethod::Get
     } else {
         &ConfigProbeServiceNodeHTTPMethod::Head
@@ -505,34 +1216,28 @@ fn proceed_replica_probe_poll_http(
         &url_bang, &effective_http_method, &effective_http_body
     );
 
-    let response = match effective_http_method {
-        ConfigProbeServiceNodeHTTPMethod::Head => PROBE_HTTP_CLIENT.head(&url_bang),
-        ConfigProbeServiceNodeHTTPMethod::Get => PROBE_HTTP_CLIENT.get(&url_bang),
-        ConfigProbeServiceNodeHTTPMethod::Post => {
-            PROBE_HTTP_CLIENT
-                .post(&url_bang)
-                .body(reqwest::blocking::Body::from(
-                    effective_http_body.to_string(),
-                ))
-        }
-        ConfigProbeServiceNodeHTTPMethod::Put => {
-            PROBE_HTTP_CLIENT
-                .put(&url_bang)
-                .body(reqwest::blocking::Body::from(
-                    effective_http_body.to_string(),
-                ))
-        }
-        ConfigProbeServiceNodeHTTPMethod::Patch => {
-            PROBE_HTTP_CLIENT
-                .patch(&url_bang)
-                .body(reqwest::blocking::Body::from(
-                    effective_http_body.to_string(),
-                ))
-        }
+    let mut effective_http_headers = http_headers.to_owned();
+
+    if let Some(http_host) = http_host {
+        effective_http_headers.entry(HOST).or_insert(http_host);
     }
-    .headers(http_headers.to_owned())
+
+    let request_time = SystemTime::now();
+
+    let response = make_http_request(
+        &http_client,
+        effective_http_method,
+        &url_bang,
+        effective_http_body,
+    )
+    .headers(effective_http_headers)
     .send();
 
+    // Acquire response time (up to the response headers being received)
+    let response_latency = SystemTime::now()
+        .duration_since(request_time)
+        .unwrap_or(Duration::from_secs(0));
+
     match response {
         Ok(response_inner) => {
             let status_code = response_inner.status().as_u16();
@@ -542,22 +1247,26 @@ fn proceed_replica_probe_poll_http(
                 &url_bang, status_code
             );
 
-            // Consider as UP?
-            if status_code >= APP_CONF.metrics.poll_http_status_healthy_above
-                && status_code < APP_CONF.metrics.poll_http_status_healthy_below
+            // Consider as UP? (status code assertions replace the default healthy range)
+            let has_status_assert = http_assert
+                .iter()
+                .any(|http_assert| http_assert.status.is_some());
+
+            if has_status_assert
+                || (status_code >= APP_CONF.metrics.poll_http_status_healthy_above
+                    && status_code < APP_CONF.metrics.poll_http_status_healthy_below)
             {
-                // Check response body for match? (if configured)
-                if let &Some(ref body_match_regex) = body_match {
+                let response_headers = response_inner.headers().to_owned();
+
+                // Acquire response text? (if it needs to be checked)
+                let response_text = if check_body {
                     if let Ok(text) = response_inner.text() {
                         debug!(
                         "checking prober poll response text for http target: {} for any match: {}",
                         &url_bang, &text
                     );
 
-                        // Doesnt match? Consider as DOWN.
-                        if body_match_regex.is_match(&text) == false {
-                            return (false, None);
-                        }
+                        Some(text)
                     } else {
                         debug!(
                             "could not unpack response text for http target: {}",
@@ -565,11 +1274,31 @@ fn proceed_replica_probe_poll_http(
                         );
 
                         // Consider as DOWN (the response text could not be checked)
-                        return (false, None);
+                        return (Status::Dead, None);
+                    }
+                } else {
+                    None
+                };
+
+                // Check response body for match? (if configured)
+                if let (Some(body_match_regex), Some(ref text)) = (body_match, &response_text) {
+                    // Doesnt match? Consider as DOWN.
+                    if !body_match_regex.is_match(text) {
+                        return (Status::Dead, None);
                     }
                 }
 
-                return (true, None);
+                return (
+                    proceed_replica_probe_poll_http_assert(
+                        &url_bang,
+                        http_assert,
+                        status_code,
+                        &response_headers,
+                        response_text.as_deref(),
+                        response_latency,
+                    ),
+                    None,
+                );
             }
         }
         Err(err) => {
@@ -581,10 +1310,440 @@ fn proceed_replica_probe_poll_http(
     }
 
     // Consider as DOWN.
-    (false, None)
+    (Status::Dead, None)
 }
 
-fn proceed_replica_probe_script(script: &String) -> (Status, Option<Duration>) {
+fn proceed_replica_probe_poll_http_steps(
+    http_client: &Client,
+    url: &str,
+    http_host: &Option<HeaderValue>,
+    probe_replica_poll: &ProbeReplicaPoll,
+) -> Status {
+    // Notice: steps URLs are relative to the replica URL (although they can be absolute), and \
+    //   variables extracted from the responses of previous steps get substituted in them.
+    let base_url = match Url::parse(url) {
+        Ok(base_url) => base_url,
+        Err(_) => return Status::Dead,
+    };
+
+    let (mut status, mut variables) = (Status::Healthy, HashMap::new());
+
+    for (index, http_step) in probe_replica_poll.http_steps.iter().enumerate() {
+        let step_url =
+            match base_url.join(&substitute_http_variables(&http_step.url, &variables, true)) {
+                Ok(step_url) => step_url,
+                Err(err) => {
+                    debug!(
+                        "could not acquire url of http step #{} for http target: {} (error: {})",
+                        index, url, err
+                    );
+
+                    return Status::Dead;
+                }
+            };
+
+        // Merge step headers over node headers
+        let mut step_headers = probe_replica_poll.http_headers.to_owned();
+
+        // Keep the replica host header on steps requesting the replica (with an SNI override)
+        if let Some(http_host) = http_host {
+            if step_url.host() == base_url.host() && step_url.port() == base_url.port() {
+                step_headers.entry(HOST).or_insert(http_host.to_owned());
+            }
+        }
+
+        let step_url = make_http_url_bang(step_url.as_str());
+
+        for (header_name, header_value) in http_step.headers.iter() {
+            let header_value = header_value.to_str().ok().and_then(|value| {
+                HeaderValue::from_str(&substitute_http_variables(value, &variables, false)).ok()
+            });
+
+            match header_value {
+                Some(header_value) => {
+                    step_headers.insert(header_name.to_owned(), header_value);
+                }
+                None => return Status::Dead,
+            }
+        }
+
+        let step_body = http_step
+            .body
+            .as_ref()
+            .map(|body| substitute_http_variables(body, &variables, false))
+            .unwrap_or_default();
+
+        let step_method = http_step
+            .method
+            .as_ref()
+            .unwrap_or(&ConfigProbeServiceNodeHTTPMethod::Get);
+
+        debug!(
+            "prober poll will fire http step #{} for http target: {} with method: {:?}",
+            index, &step_url, step_method
+        );
+
+        let request_time = SystemTime::now();
+
+        let response = make_http_request(http_client, step_method, &step_url, &step_body)
+            .headers(step_headers)
+            .send();
+
+        let response_latency = SystemTime::now()
+            .duration_since(request_time)
+            .unwrap_or(Duration::from_secs(0));
+
+        let response_inner = match response {
+            Ok(response_inner) => response_inner,
+            Err(err) => {
+                debug!(
+                    "prober poll result was not received for http step #{} for http target: {} \
+                        (error: {})",
+                    index, &step_url, err
+                );
+
+                return Status::Dead;
+            }
+        };
+
+        let status_code = response_inner.status().as_u16();
+
+        debug!(
+            "prober poll result received for http step #{} for http target: {} with status: {}",
+            index, &step_url, status_code
+        );
+
+        // Consider as DOWN? (status code assertions replace the default healthy range)
+        let has_status_assert = http_step
+            .assert
+            .iter()
+            .any(|http_assert| http_assert.status.is_some());
+
+        if !has_status_assert
+            && (status_code < APP_CONF.metrics.poll_http_status_healthy_above
+                || status_code >= APP_CONF.metrics.poll_http_status_healthy_below)
+        {
+            return Status::Dead;
+        }
+
+        let response_headers = response_inner.headers().to_owned();
+
+        let response_text = match response_inner.text() {
+            Ok(response_text) => response_text,
+            Err(_) => return Status::Dead,
+        };
+
+        // Check step assertions (a dead step ends the transaction)
+        match proceed_replica_probe_poll_http_assert(
+            &step_url,
+            &http_step.assert,
+            status_code,
+            &response_headers,
+            Some(&response_text),
+            response_latency,
+        ) {
+            Status::Dead => return Status::Dead,
+            Status::Sick => status = Status::Sick,
+            Status::Healthy => {}
+        }
+
+        // Extract variables for next steps (a missing variable ends the transaction)
+        let response_json = if http_step
+            .extract
+            .iter()
+            .any(|extract| extract.json_path.is_some())
+        {
+            serde_json::from_str::<JSONValue>(&response_text).ok()
+        } else {
+            None
+        };
+
+        for extract in &http_step.extract {
+            match extract_http_variable(extract, &response_headers, &response_json) {
+                Some(value) => {
+                    variables.insert(extract.name.to_owned(), value);
+                }
+                None => {
+                    debug!(
+                        "could not extract variable: {} from http step #{} for http target: {}",
+                        extract.name, index, &step_url
+                    );
+
+                    return Status::Dead;
+                }
+            }
+        }
+    }
+
+    status
+}
+
+fn extract_http_variable(
+    extract: &ConfigProbeServiceNodeHTTPStepExtract,
+    headers: &HeaderMap,
+    json: &Option<JSONValue>,
+) -> Option<String> {
+    if let Some(ref header) = extract.header {
+        return headers
+            .get(header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+    }
+
+    if let (Some(ref json_path), Some(ref json)) = (&extract.json_path, json) {
+        // Use the first matched value (strings are used as-is, other values as JSON)
+        return json_path
+            .query(json)
+            .all()
+            .first()
+            .map(|value| match value {
+                JSONValue::String(value) => value.to_owned(),
+                value => value.to_string(),
+            });
+    }
+
+    None
+}
+
+fn substitute_http_variables(
+    template: &str,
+    variables: &HashMap<String, String>,
+    url_encode: bool,
+) -> String {
+    let mut value = template.to_string();
+
+    for (name, variable) in variables.iter() {
+        // Notice: variables substituted in URLs are percent-encoded, so that they cannot alter \
+        //   the URL structure (eg. with '/', '?' or '#' characters). Headers and bodies get them \
+        //   as-is.
+        let variable = if url_encode {
+            utf8_percent_encode(variable, PROBE_HTTP_URL_VARIABLE_ENCODE_SET).to_string()
+        } else {
+            variable.to_owned()
+        };
+
+        value = value.replace(&format!("{{{{{}}}}}", name), &variable);
+    }
+
+    value
+}
+
+fn make_http_url_bang(url: &str) -> String {
+    // Acquire query string separator (if the URL already contains a query string, use append mode)
+    let query_separator = if url.contains("?") { "&" } else { "?" };
+
+    // Generate URL with cache buster, to bypass any upstream cache (eg. CDN cache layer)
+    format!(
+        "{}{}{}",
+        url,
+        query_separator,
+        time::OffsetDateTime::now_utc().unix_timestamp()
+    )
+}
+
+fn make_http_request(
+    http_client: &Client,
+    http_method: &ConfigProbeServiceNodeHTTPMethod,
+    url: &str,
+    http_body: &str,
+) -> RequestBuilder {
+    match http_method {
+        ConfigProbeServiceNodeHTTPMethod::Head => http_client.head(url),
+        ConfigProbeServiceNodeHTTPMethod::Get => http_client.get(url),
+        ConfigProbeServiceNodeHTTPMethod::Post => http_client
+            .post(url)
+            .body(reqwest::blocking::Body::from(http_body.to_string())),
+        ConfigProbeServiceNodeHTTPMethod::Put => http_client
+            .put(url)
+            .body(reqwest::blocking::Body::from(http_body.to_string())),
+        ConfigProbeServiceNodeHTTPMethod::Patch => http_client
+            .patch(url)
+            .body(reqwest::blocking::Body::from(http_body.to_string())),
+    }
+}
+
+fn acquire_http_client(
+    url: &str,
+    http_client: &Option<ServiceStatesProbeNodeHTTPClient>,
+) -> Result<(Client, String, Option<HeaderValue>), String> {
+    let http_client = match http_client {
+        Some(http_client) => http_client,
+        None => return Ok((PROBE_HTTP_CLIENT.to_owned(), url.to_owned(), None)),
+    };
+
+    match (&http_client.client, &http_client.tls_sni) {
+        (_, Some(tls_sni)) => {
+            // Notice: the SNI is overridden by requesting the SNI host name instead of the \
+            //   replica host, while resolving it to the addresses of the replica host. The \
+            //   replica host is still sent in the 'Host' header, so that only the SNI changes.
+            let mut sni_url = Url::parse(url).map_err(|err| err.to_string())?;
+
+            let host = match (sni_url.host_str(), sni_url.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                (None, _) => return Err("url has no host".to_string()),
+            };
+
+            let addresses = resolve_url_addresses(&sni_url)?;
+
+            sni_url
+                .set_host(Some(tls_sni))
+                .map_err(|err| err.to_string())?;
+
+            let client = acquire_http_sni_client(url, http_client, tls_sni, addresses)?;
+
+            Ok((
+                client,
+                sni_url.to_string(),
+                Some(HeaderValue::from_str(&host).map_err(|err| err.to_string())?),
+            ))
+        }
+        (Some(client), None) => Ok((client.to_owned(), url.to_owned(), None)),
+        (None, None) => Ok((PROBE_HTTP_CLIENT.to_owned(), url.to_owned(), None)),
+    }
+}
+
+fn acquire_http_sni_client(
+    url: &str,
+    http_client: &ServiceStatesProbeNodeHTTPClient,
+    tls_sni: &str,
+    addresses: Vec<SocketAddr>,
+) -> Result<Client, String> {
+    // Notice: replica addresses are resolved upon each probe, as they may change over time. The \
+    //   client of the replica is only rebuilt if its addresses changed.
+    if let Some((client_addresses, client)) = http_client.sni_clients.read().unwrap().get(url) {
+        if *client_addresses == addresses {
+            return Ok(client.to_owned());
+        }
+    }
+
+    let client = make_http_client(Some(http_client), Some((tls_sni, &addresses)))?;
+
+    http_client
+        .sni_clients
+        .write()
+        .unwrap()
+        .insert(url.to_owned(), (addresses, client.to_owned()));
+
+    Ok(client)
+}
+
+fn resolve_url_addresses(url: &Url) -> Result<Vec<SocketAddr>, String> {
+    let port = url.port_or_known_default().unwrap_or(0);
+
+    match url.host() {
+        Some(Host::Domain(domain)) => Ok((domain, port)
+            .to_socket_addrs()
+            .map_err(|err| err.to_string())?
+            .collect()),
+        Some(Host::Ipv4(address)) => Ok(vec![SocketAddr::new(IpAddr::V4(address), port)]),
+        Some(Host::Ipv6(address)) => Ok(vec![SocketAddr::new(IpAddr::V6(address), port)]),
+        None => Err("url has no host".to_string()),
+    }
+}
+
+fn proceed_replica_probe_poll_http_assert(
+    url: &str,
+    http_assert: &[ConfigProbeServiceNodeHTTPAssert],
+    status_code: u16,
+    headers: &HeaderMap,
+    text: Option<&str>,
+    latency: Duration,
+) -> Status {
+    // Parse response JSON? (once, if any assertion checks it)
+    let json = if http_assert
+        .iter()
+        .any(|http_assert| http_assert.json_path.is_some())
+    {
+        text.and_then(|text| serde_json::from_str::<JSONValue>(text).ok())
+    } else {
+        None
+    };
+
+    let mut status = Status::Healthy;
+
+    for http_assert in http_assert {
+        if !check_http_assert(http_assert, status_code, headers, text, &json, latency) {
+            debug!(
+                "http assertion failed for http target: {} ({:?})",
+                url, http_assert
+            );
+
+            // Keep the worst failure status (a dead failure cannot get any worse)
+            if http_assert.failure == Status::Dead {
+                return Status::Dead;
+            }
+
+            status = http_assert.failure;
+        }
+    }
+
+    status
+}
+
+fn check_http_assert(
+    http_assert: &ConfigProbeServiceNodeHTTPAssert,
+    status_code: u16,
+    headers: &HeaderMap,
+    text: Option<&str>,
+    json: &Option<JSONValue>,
+    latency: Duration,
+) -> bool {
+    // Notice: assertions are validated upon registration, so that only one check is set on each.
+    if let Some(ref status_codes) = http_assert.status {
+        return status_codes.contains(&status_code);
+    }
+
+    if let Some(ref header) = http_assert.header {
+        return match (headers.get(header.as_str()), &http_assert.header_match) {
+            (Some(value), Some(header_match)) => value
+                .to_str()
+                .map(|value| header_match.is_match(value))
+                .unwrap_or(false),
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+    }
+
+    if let Some(ref json_path) = http_assert.json_path {
+        // Any of the matched nodes satisfies the assertion (a value-less assertion checks that \
+        //   the path exists)
+        return match json {
+            Some(json) => json_path.query(json).all().into_iter().any(|node| {
+                match (&http_assert.json_equals, &http_assert.json_match) {
+                    (Some(json_equals), _) => node == json_equals,
+                    (None, Some(json_match)) => match node {
+                        JSONValue::String(value) => json_match.is_match(value),
+                        value => json_match.is_match(&value.to_string()),
+                    },
+                    (None, None) => true,
+                }
+            }),
+            None => false,
+        };
+    }
+
+    if http_assert.body_size_above.is_some() || http_assert.body_size_below.is_some() {
+        let body_size = text.map(|text| text.len()).unwrap_or(0);
+
+        return http_assert
+            .body_size_above
+            .map(|above| body_size > above)
+            .unwrap_or(true)
+            && http_assert
+                .body_size_below
+                .map(|below| body_size < below)
+                .unwrap_or(true);
+    }
+
+    if let Some(latency_below) = http_assert.latency_below {
+        return latency < Duration::from_millis(latency_below);
+    }
+
+    true
+}
+
+fn proceed_replica_probe_script(script: &str) -> (Status, Option<Duration>) {
     let start_time = SystemTime::now();
 
     let status = match run_script::run(script, &Vec::new(), &ScriptOptions::new()) {
@@ -717,13 +1876,7 @@ fn dispatch_replica<'a>(probe_replica: &ProbeReplica) {
             node_id = &probe_replica_target.node_id;
             replica_id = &probe_replica_target.replica_id;
 
-            proceed_replica_probe_poll_with_retry(
-                &probe_replica_poll.replica_url,
-                &probe_replica_poll.http_headers,
-                &probe_replica_poll.http_method,
-                &probe_replica_poll.http_body,
-                &probe_replica_poll.body_match,
-            )
+            proceed_replica_probe_poll_with_retry(probe_replica_poll)
         }
         ProbeReplica::Script(probe_replica_target, probe_replica_script) => {
             probe_id = &probe_replica_target.probe_id;
@@ -746,7 +1899,12 @@ fn dispatch_replica<'a>(probe_replica: &ProbeReplica) {
         if let Some(ref mut probe) = store.states.probes.get_mut(probe_id) {
             if let Some(ref mut node) = probe.nodes.get_mut(node_id) {
                 if let Some(ref mut replica) = node.replicas.get_mut(replica_id) {
+                    if replica.status != replica_status {
+                        replica.changed = Some(SystemTime::now());
+                    }
+
                     replica.status = replica_status;
+                    replica.checked = Some(SystemTime::now());
 
                     replica.metrics.latency =
                         replica_latency.map(|duration| duration.as_millis() as u64);
@@ -919,99 +2077,19 @@ pub fn initialize_store() {
     let mut store = STORE.write().unwrap();
 
     for service in &APP_CONF.probe.service {
-        let mut probe = ServiceStatesProbe {
-            id: service.id.to_owned(),
-            label: service.label.to_owned(),
-            status: Status::Healthy,
-            nodes: IndexMap::new(),
-        };
-
-        debug!("prober store: got service {}", service.id);
-
-        for node in &service.node {
-            debug!("prober store: got node {}:{}", service.id, node.id);
-
-            let mut probe_node = ServiceStatesProbeNode {
-                status: Status::Healthy,
-                label: node.label.to_owned(),
-                mode: node.mode.to_owned(),
-                replicas: IndexMap::new(),
-                http_headers: node.http_headers.to_owned(),
-                http_method: node.http_method.to_owned(),
-                http_body: node.http_body.to_owned(),
-                http_body_healthy_match: node.http_body_healthy_match.to_owned(),
-                reveal_replica_name: node.reveal_replica_name,
-                link_url: node.link_url.as_ref().map(|url| url.to_string()),
-                link_label: node.link_label.to_owned(),
-                rabbitmq: node.rabbitmq_queue.as_ref().map(|queue| {
-                    ServiceStatesProbeNodeRabbitMQ {
-                        queue: queue.to_owned(),
-                        queue_nack_healthy_below: node.rabbitmq_queue_nack_healthy_below,
-                        queue_nack_dead_above: node.rabbitmq_queue_nack_dead_above,
-                    }
-                }),
-            };
-
-            // Node with replicas? (might be a poll node)
-            if let Some(ref replicas) = node.replicas {
-                if node.mode != Mode::Poll {
-                    panic!("non-poll node cannot have replicas");
-                }
-
-                for replica in replicas {
-                    debug!(
-                        "prober store: got replica {}:{}:{}",
-                        service.id, node.id, replica
-                    );
-
-                    let replica_url = ReplicaURL::parse_from(replica).expect("invalid replica url");
-
-                    probe_node.replicas.insert(
-                        replica.to_string(),
-                        ServiceStatesProbeNodeReplica {
-                            status: Status::Healthy,
-                            url: Some(replica_url),
-                            script: None,
-                            metrics: ServiceStatesProbeNodeReplicaMetrics::default(),
-                            load: None,
-                            report: None,
-                        },
-                    );
-                }
-            }
-
-            // Node with scripts? (might be a script node)
-            if let Some(ref scripts) = node.scripts {
-                if node.mode != Mode::Script {
-                    panic!("non-script node cannot have scripts");
-                }
-
-                for (index, script) in scripts.iter().enumerate() {
-                    debug!(
-                        "prober store: got script {}:{}:#{}",
-                        service.id, node.id, index
-                    );
-
-                    probe_node.replicas.insert(
-                        index.to_string(),
-                        ServiceStatesProbeNodeReplica {
-                            status: Status::Healthy,
-                            url: None,
-                            script: Some(script.to_owned()),
-                            metrics: ServiceStatesProbeNodeReplicaMetrics::default(),
-                            load: None,
-                            report: None,
-                        },
-                    );
-                }
-            }
-
-            probe.nodes.insert(node.id.to_owned(), probe_node);
-        }
+        let probe = make_probe(service).unwrap_or_else(|err| {
+            panic!(
+                "could not initialize service: {} ({})",
+                service.id,
+                err.as_str()
+            )
+        });
 
         store.states.probes.insert(service.id.to_owned(), probe);
     }
 
+    label_dependencies(&mut store.states.probes);
+
     info!("initialized prober store");
 }
 
@@ -6,8 +6,13 @@
 
 mod replica;
 
+pub mod aggregate;
+pub mod dependency;
+pub mod discover;
 pub mod manager;
 pub mod mode;
+pub mod registry;
 pub mod report;
 pub mod states;
 pub mod status;
+pub mod visibility;
@@ -0,0 +1,962 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use indexmap::IndexMap;
+
+use super::aggregate::AggregatePolicy;
+use super::dependency::{
+    validate as validate_dependency_graph, DependencyError, DependencyNode, DependencyService,
+};
+use super::discover::{DiscoverEndpoint, DiscoverSource};
+use super::mode::Mode;
+use super::replica::ReplicaURL;
+use super::states::{
+    ServiceStatesDependency, ServiceStatesProbe, ServiceStatesProbeNode,
+    ServiceStatesProbeNodeDiscover, ServiceStatesProbeNodeHTTPClient,
+    ServiceStatesProbeNodeRabbitMQ, ServiceStatesProbeNodeReplica,
+    ServiceStatesProbeNodeReplicaMetrics, ServiceStatesProbeNodeTCP, ServiceStatesUptime,
+};
+use super::status::Status;
+use crate::config::config::{
+    ConfigProbeService, ConfigProbeServiceNode, ConfigProbeServiceNodeHTTPAssert,
+    ConfigProbeServiceNodePayloadEncoding, ConfigProbeServiceNodeTCPTLS,
+};
+use crate::prober::manager::{make_http_client, STORE as PROBER_STORE};
+
+#[derive(Debug)]
+pub enum RegistryError {
+    NotFound,
+    DuplicateNode,
+    DuplicateReplica,
+    WrongMode,
+    InvalidReplica,
+    InvalidDiscover,
+    InvalidAssert,
+    InvalidHTTPClient,
+    InvalidHTTPStep,
+    InvalidPayload,
+    InvalidTCPOptions,
+    InvalidAggregate(&'static str),
+    UnknownDependency,
+    DependencyCycle,
+    DependedOn,
+}
+
+impl RegistryError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegistryError::NotFound => "service, node or replica not found",
+            RegistryError::DuplicateNode => "duplicate node identifier",
+            RegistryError::DuplicateReplica => "duplicate replica",
+            RegistryError::WrongMode => "node mode does not accept these replicas",
+            RegistryError::InvalidReplica => "invalid replica",
+            RegistryError::InvalidDiscover => "invalid discovery source or template",
+            RegistryError::InvalidAssert => "invalid http assertion",
+            RegistryError::InvalidHTTPClient => "invalid http client options",
+            RegistryError::InvalidHTTPStep => "invalid http step",
+            RegistryError::InvalidPayload => "invalid payload",
+            RegistryError::InvalidTCPOptions => "invalid tcp options",
+            RegistryError::InvalidAggregate(reason) => reason,
+            RegistryError::UnknownDependency => "unknown dependency",
+            RegistryError::DependencyCycle => "dependency cycle",
+            RegistryError::DependedOn => "other services or nodes depend on it",
+        }
+    }
+}
+
+pub fn make_probe(service: &ConfigProbeService) -> Result<ServiceStatesProbe, RegistryError> {
+    let mut probe = ServiceStatesProbe {
+        id: service.id.to_owned(),
+        label: service.label.to_owned(),
+        status: Status::Healthy,
+        visibility: service.visibility,
+        depends_on: service
+            .depends_on
+            .iter()
+            .map(|reference| make_dependency(reference))
+            .collect(),
+        impacted_by: Vec::new(),
+        aggregate: AggregatePolicy::from_config(
+            service.aggregate,
+            service.aggregate_quorum,
+            service.aggregate_minimum,
+        )
+        .map_err(RegistryError::InvalidAggregate)?,
+        uptime: ServiceStatesUptime::default(),
+        changed: None,
+        nodes: IndexMap::new(),
+    };
+
+    debug!("prober registry: got service {}", service.id);
+
+    for node in &service.node {
+        // Node identifier was already previously inserted? (caught a duplicate)
+        if probe.nodes.contains_key(&node.id) {
+            return Err(RegistryError::DuplicateNode);
+        }
+
+        probe
+            .nodes
+            .insert(node.id.to_owned(), make_node(&service.id, node)?);
+    }
+
+    Ok(probe)
+}
+
+pub fn make_node(
+    probe_id: &str,
+    node: &ConfigProbeServiceNode,
+) -> Result<ServiceStatesProbeNode, RegistryError> {
+    debug!("prober registry: got node {}:{}", probe_id, node.id);
+
+    let mut probe_node = ServiceStatesProbeNode {
+        status: Status::Healthy,
+        label: node.label.to_owned(),
+        mode: node.mode.to_owned(),
+        visibility: node.visibility,
+        depends_on: node
+            .depends_on
+            .iter()
+            .map(|reference| make_dependency(reference))
+            .collect(),
+        impacted_by: Vec::new(),
+        aggregate: AggregatePolicy::from_config(
+            node.aggregate,
+            node.aggregate_quorum,
+            node.aggregate_minimum,
+        )
+        .map_err(RegistryError::InvalidAggregate)?,
+        uptime: ServiceStatesUptime::default(),
+        changed: None,
+        replicas: IndexMap::new(),
+        http_headers: node.http_headers.to_owned(),
+        http_method: node.http_method.to_owned(),
+        http_body: node.http_body.to_owned(),
+        http_body_healthy_match: node.http_body_healthy_match.to_owned(),
+        http_assert: node.http_assert.to_owned(),
+        http_steps: node.http_steps.to_owned(),
+        http_client: make_http_client_options(probe_id, node)?,
+        websocket_message: node.websocket_message.to_owned(),
+        websocket_reply_healthy_match: node.websocket_reply_healthy_match.to_owned(),
+        udp_payload: node
+            .udp_payload
+            .as_ref()
+            .map(|payload| decode_payload(payload, node.udp_payload_encoding))
+            .transpose()?,
+        udp_payload_encoding: node.udp_payload_encoding,
+        udp_reply_healthy_match: node.udp_reply_healthy_match.to_owned(),
+        tcp: make_tcp_options(node)?,
+        reveal_replica_name: node.reveal_replica_name,
+        link_url: node.link_url.as_ref().map(|url| url.to_string()),
+        link_label: node.link_label.to_owned(),
+        rabbitmq: node
+            .rabbitmq_queue
+            .as_ref()
+            .map(|queue| ServiceStatesProbeNodeRabbitMQ {
+                queue: queue.to_owned(),
+                queue_nack_healthy_below: node.rabbitmq_queue_nack_healthy_below,
+                queue_nack_dead_above: node.rabbitmq_queue_nack_dead_above,
+            }),
+        discover: make_discover(node)?,
+    };
+
+    // Node with a discovery source or protocol options? (might be a poll node)
+    if (probe_node.discover.is_some()
+        || !probe_node.http_assert.is_empty()
+        || !probe_node.http_steps.is_empty()
+        || probe_node.http_client.is_some()
+        || probe_node.websocket_message.is_some()
+        || probe_node.websocket_reply_healthy_match.is_some()
+        || probe_node.udp_payload.is_some()
+        || probe_node.udp_reply_healthy_match.is_some()
+        || probe_node.tcp.is_some())
+        && probe_node.mode != Mode::Poll
+    {
+        return Err(RegistryError::WrongMode);
+    }
+
+    for http_assert in &probe_node.http_assert {
+        validate_http_assert(http_assert)?;
+    }
+
+    if !probe_node.http_steps.is_empty() {
+        validate_http_steps(node)?;
+    }
+
+    // Node with replicas? (might be a poll node)
+    if let Some(ref replicas) = node.replicas {
+        for replica in replicas {
+            debug!(
+                "prober registry: got replica {}:{}:{}",
+                probe_id, node.id, replica
+            );
+
+            let (replica_id, replica) = make_replica(&probe_node, Some(replica), None)?;
+
+            probe_node.replicas.insert(replica_id, replica);
+        }
+    }
+
+    // Node with scripts? (might be a script node)
+    if let Some(ref scripts) = node.scripts {
+        for script in scripts {
+            debug!(
+                "prober registry: got script {}:{}:#{}",
+                probe_id,
+                node.id,
+                probe_node.replicas.len()
+            );
+
+            let (replica_id, replica) = make_replica(&probe_node, None, Some(script))?;
+
+            probe_node.replicas.insert(replica_id, replica);
+        }
+    }
+
+    Ok(probe_node)
+}
+
+pub fn upsert_service(service: &ConfigProbeService) -> Result<bool, RegistryError> {
+    let probe = make_probe(service)?;
+
+    // Notice: replaced services are dropped once the store lock is released (as this is \
+    //   declared before the lock guard), as dropping their HTTP clients waits for the client \
+    //   threads to stop.
+    let _replaced_probe;
+
+    let mut store = PROBER_STORE.write().unwrap();
+    let probes = &mut store.states.probes;
+
+    // Notice: the service is inserted before dependencies get validated, as other services may \
+    //   depend on it (eg. if a dependency on one of its nodes was removed); it gets restored if \
+    //   validation fails.
+    let previous_probe = probes.insert(service.id.to_owned(), probe);
+
+    if let Err(err) = validate_dependencies(probes) {
+        _replaced_probe = match previous_probe {
+            Some(previous_probe) => probes.insert(service.id.to_owned(), previous_probe),
+            None => probes.shift_remove(&service.id),
+        };
+
+        return Err(err);
+    }
+
+    // Carry over runtime states from the replaced service (if any)
+    let created = match previous_probe {
+        Some(mut previous_probe) => {
+            if let Some(probe) = probes.get_mut(&service.id) {
+                carry_probe(probe, &mut previous_probe);
+            }
+
+            _replaced_probe = Some(previous_probe);
+
+            false
+        }
+        None => true,
+    };
+
+    label_dependencies(probes);
+
+    info!("prober registry: upserted service {}", service.id);
+
+    Ok(created)
+}
+
+pub fn remove_service(probe_id: &str) -> Result<(), RegistryError> {
+    // Notice: the removed service is dropped once the store lock is released
+    let _removed_probe;
+
+    let mut store = PROBER_STORE.write().unwrap();
+    let probes = &mut store.states.probes;
+
+    if !probes.contains_key(probe_id) {
+        return Err(RegistryError::NotFound);
+    }
+
+    if is_depended_on(probes, probe_id, None) {
+        return Err(RegistryError::DependedOn);
+    }
+
+    _removed_probe = probes.shift_remove(probe_id);
+
+    info!("prober registry: removed service {}", probe_id);
+
+    Ok(())
+}
+
+pub fn upsert_node(probe_id: &str, node: &ConfigProbeServiceNode) -> Result<bool, RegistryError> {
+    let probe_node = make_node(probe_id, node)?;
+
+    // Notice: the replaced node is dropped once the store lock is released
+    let _replaced_node;
+
+    let mut store = PROBER_STORE.write().unwrap();
+    let probes = &mut store.states.probes;
+
+    let previous_node = probes
+        .get_mut(probe_id)
+        .ok_or(RegistryError::NotFound)?
+        .nodes
+        .insert(node.id.to_owned(), probe_node);
+
+    if let Err(err) = validate_dependencies(probes) {
+        if let Some(probe) = probes.get_mut(probe_id) {
+            _replaced_node = match previous_node {
+                Some(previous_node) => probe.nodes.insert(node.id.to_owned(), previous_node),
+                None => probe.nodes.shift_remove(&node.id),
+            };
+        }
+
+        return Err(err);
+    }
+
+    // Carry over runtime states from the replaced node (if any)
+    let created = match previous_node {
+        Some(mut previous_node) => {
+            if let Some(probe_node) = probes
+                .get_mut(probe_id)
+                .and_then(|probe| probe.nodes.get_mut(&node.id))
+            {
+                carry_node(probe_node, &mut previous_node);
+            }
+
+            _replaced_node = Some(previous_node);
+
+            false
+        }
+        None => true,
+    };
+
+    label_dependencies(probes);
+
+    info!("prober registry: upserted node {}:{}", probe_id, node.id);
+
+    Ok(created)
+}
+
+pub fn remove_node(probe_id: &str, node_id: &str) -> Result<(), RegistryError> {
+    // Notice: the removed node is dropped once the store lock is released
+    let _removed_node;
+
+    let mut store = PROBER_STORE.write().unwrap();
+    let probes = &mut store.states.probes;
+
+    let probe = probes.get(probe_id).ok_or(RegistryError::NotFound)?;
+
+    if !probe.nodes.contains_key(node_id) {
+        return Err(RegistryError::NotFound);
+    }
+
+    // Notice: the last node of a service cannot be removed if the service is depended on, as \
+    //   dependencies on a service expand to all of its nodes.
+    if is_depended_on(probes, probe_id, Some(node_id))
+        || (probe.nodes.len() == 1 && is_depended_on(probes, probe_id, None))
+    {
+        return Err(RegistryError::DependedOn);
+    }
+
+    _removed_node = probes
+        .get_mut(probe_id)
+        .and_then(|probe| probe.nodes.shift_remove(node_id));
+
+    info!("prober registry: removed node {}:{}", probe_id, node_id);
+
+    Ok(())
+}
+
+pub fn insert_replica(
+    probe_id: &str,
+    node_id: &str,
+    replica: Option<&str>,
+    script: Option<&str>,
+) -> Result<String, RegistryError> {
+    let mut store = PROBER_STORE.write().unwrap();
+
+    let probe_node = store
+        .states
+        .probes
+        .get_mut(probe_id)
+        .and_then(|probe| probe.nodes.get_mut(node_id))
+        .ok_or(RegistryError::NotFound)?;
+
+    let (replica_id, probe_replica) = make_replica(probe_node, replica, script)?;
+
+    probe_node
+        .replicas
+        .insert(replica_id.to_owned(), probe_replica);
+
+    info!(
+        "prober registry: inserted replica {}:{}:{}",
+        probe_id, node_id, replica_id
+    );
+
+    Ok(replica_id)
+}
+
+pub fn remove_replica(
+    probe_id: &str,
+    node_id: &str,
+    replica_id: &str,
+) -> Result<(), RegistryError> {
+    let mut store = PROBER_STORE.write().unwrap();
+
+    let probe_node = store
+        .states
+        .probes
+        .get_mut(probe_id)
+        .and_then(|probe| probe.nodes.get_mut(node_id))
+        .ok_or(RegistryError::NotFound)?;
+
+    // Notice: 'push' and 'local' replicas are removed by flushing them via the reporter API
+    if probe_node.mode != Mode::Poll && probe_node.mode != Mode::Script {
+        return Err(RegistryError::WrongMode);
+    }
+
+    probe_node
+        .replicas
+        .shift_remove(replica_id)
+        .ok_or(RegistryError::NotFound)?;
+
+    info!(
+        "prober registry: removed replica {}:{}:{}",
+        probe_id, node_id, replica_id
+    );
+
+    Ok(())
+}
+
+pub fn label_dependencies(probes: &mut IndexMap<String, ServiceStatesProbe>) {
+    // Acquire all labels (dependencies are referenced as 'service' or 'service:node')
+    let mut labels = HashMap::new();
+
+    for (probe_id, probe) in probes.iter() {
+        labels.insert(probe_id.to_owned(), probe.label.to_owned());
+
+        for (node_id, node) in probe.nodes.iter() {
+            labels.insert(
+                format!("{}:{}", probe_id, node_id),
+                format!("{} / {}", probe.label, node.label),
+            );
+        }
+    }
+
+    for probe in probes.values_mut() {
+        let nodes_depends_on = probe
+            .nodes
+            .values_mut()
+            .flat_map(|node| node.depends_on.iter_mut());
+
+        for dependency in probe.depends_on.iter_mut().chain(nodes_depends_on) {
+            if let Some(label) = labels.get(&dependency.id) {
+                dependency.label = label.to_owned();
+            }
+        }
+    }
+}
+
+fn make_discover(
+    node: &ConfigProbeServiceNode,
+) -> Result<Option<ServiceStatesProbeNodeDiscover>, RegistryError> {
+    let source = match (
+        &node.discover_dns_srv,
+        &node.discover_dns_host,
+        &node.discover_file,
+    ) {
+        (Some(name), None, None) => DiscoverSource::DnsSrv(name.to_owned()),
+        (None, Some(name), None) => DiscoverSource::DnsHost(name.to_owned()),
+        (None, None, Some(path)) => DiscoverSource::File(path.to_owned()),
+        (None, None, None) => return Ok(None),
+        _ => return Err(RegistryError::InvalidDiscover),
+    };
+
+    // Notice: files list full replica URLs, while DNS records get expanded into replica URLs \
+    //   using a template (which is checked against a sample endpoint).
+    let template = match source {
+        DiscoverSource::DnsSrv(_) | DiscoverSource::DnsHost(_) => {
+            let template = node
+                .discover_template
+                .to_owned()
+                .unwrap_or_else(|| source.default_template().to_string());
+
+            let sample_endpoint = DiscoverEndpoint {
+                host: "localhost".to_string(),
+                port: match source {
+                    DiscoverSource::DnsSrv(_) => Some(1),
+                    _ => None,
+                },
+            };
+
+            if ReplicaURL::parse_from(&sample_endpoint.expand(&template)).is_err() {
+                return Err(RegistryError::InvalidDiscover);
+            }
+
+            Some(template)
+        }
+        DiscoverSource::File(_) => {
+            if node.discover_template.is_some() {
+                return Err(RegistryError::InvalidDiscover);
+            }
+
+            None
+        }
+    };
+
+    Ok(Some(ServiceStatesProbeNodeDiscover {
+        source,
+        template,
+        replicas: Vec::new(),
+        modified: None,
+    }))
+}
+
+fn make_http_client_options(
+    probe_id: &str,
+    node: &ConfigProbeServiceNode,
+) -> Result<Option<ServiceStatesProbeNodeHTTPClient>, RegistryError> {
+    // Node uses the default HTTP client? (no HTTP client option set)
+    if node.http_redirects == 0
+        && node.http_tls_ca.is_none()
+        && node.http_tls_cert.is_none()
+        && node.http_tls_key.is_none()
+        && !node.http_tls_insecure
+        && node.http_tls_sni.is_none()
+        && node.http_ip_version.is_none()
+    {
+        return Ok(None);
+    }
+
+    // Client certificates must come with their private key
+    if node.http_tls_cert.is_some() != node.http_tls_key.is_some() {
+        return Err(RegistryError::InvalidHTTPClient);
+    }
+
+    let mut http_client = ServiceStatesProbeNodeHTTPClient {
+        redirects: node.http_redirects,
+        tls_ca: node.http_tls_ca.to_owned(),
+        tls_cert: node.http_tls_cert.to_owned(),
+        tls_key: node.http_tls_key.to_owned(),
+        tls_insecure: node.http_tls_insecure,
+        tls_sni: node.http_tls_sni.to_owned(),
+        ip_version: node.http_ip_version,
+        client: None,
+        sni_clients: Arc::new(RwLock::new(HashMap::new())),
+    };
+
+    // Notice: the client is built once there, which also checks that TLS files can be loaded. \
+    //   Clients with an SNI override cannot be shared though, as they are built for each \
+    //   replica with its resolved addresses (they are cached in 'sni_clients').
+    let client = make_http_client(Some(&http_client), None).map_err(|err| {
+        warn!(
+            "prober registry: could not build http client for node {}:{} ({})",
+            probe_id, node.id, err
+        );
+
+        RegistryError::InvalidHTTPClient
+    })?;
+
+    if http_client.tls_sni.is_none() {
+        http_client.client = Some(client);
+    }
+
+    Ok(Some(http_client))
+}
+
+fn make_tcp_options(
+    node: &ConfigProbeServiceNode,
+) -> Result<Option<ServiceStatesProbeNodeTCP>, RegistryError> {
+    // Node only checks that TCP connections open? (no TCP option set)
+    if node.tcp_banner_healthy_match.is_none()
+        && node.tcp_payload.is_none()
+        && node.tcp_reply_healthy_match.is_none()
+        && node.tcp_tls.is_none()
+        && node.tcp_starttls.is_empty()
+        && !node.tcp_tls_insecure
+    {
+        return Ok(None);
+    }
+
+    // STARTTLS exchanges must be set along with STARTTLS (and TLS options along with TLS)
+    if (node.tcp_tls == Some(ConfigProbeServiceNodeTCPTLS::StartTLS))
+        == node.tcp_starttls.is_empty()
+        || (node.tcp_tls_insecure && node.tcp_tls.is_none())
+    {
+        return Err(RegistryError::InvalidTCPOptions);
+    }
+
+    Ok(Some(ServiceStatesProbeNodeTCP {
+        banner_healthy_match: node.tcp_banner_healthy_match.to_owned(),
+        payload: node
+            .tcp_payload
+            .as_ref()
+            .map(|payload| decode_payload(payload, node.tcp_payload_encoding))
+            .transpose()?,
+        payload_encoding: node.tcp_payload_encoding,
+        reply_healthy_match: node.tcp_reply_healthy_match.to_owned(),
+        tls: node.tcp_tls,
+        starttls: node.tcp_starttls.to_owned(),
+        tls_insecure: node.tcp_tls_insecure,
+    }))
+}
+
+fn decode_payload(
+    payload: &str,
+    encoding: ConfigProbeServiceNodePayloadEncoding,
+) -> Result<Vec<u8>, RegistryError> {
+    match encoding {
+        ConfigProbeServiceNodePayloadEncoding::Text => Ok(payload.as_bytes().to_vec()),
+        ConfigProbeServiceNodePayloadEncoding::Hex => {
+            // Notice: whitespaces are allowed between bytes, eg. 'ff ff ff ff 54'.
+            let digits: Vec<char> = payload.chars().filter(|c| !c.is_whitespace()).collect();
+
+            if !digits.len().is_multiple_of(2) || !digits.iter().all(char::is_ascii_hexdigit) {
+                return Err(RegistryError::InvalidPayload);
+            }
+
+            digits
+                .chunks(2)
+                .map(|pair| {
+                    u8::from_str_radix(&pair.iter().collect::<String>(), 16)
+                        .or(Err(RegistryError::InvalidPayload))
+                })
+                .collect()
+        }
+    }
+}
+
+fn validate_http_steps(node: &ConfigProbeServiceNode) -> Result<(), RegistryError> {
+    // Notice: single request options do not apply to nodes running HTTP steps, which set their \
+    //   own method, body and assertions on each step.
+    if node.http_method.is_some()
+        || node.http_body.is_some()
+        || node.http_body_healthy_match.is_some()
+        || !node.http_assert.is_empty()
+    {
+        return Err(RegistryError::InvalidHTTPStep);
+    }
+
+    for http_step in &node.http_steps {
+        for extract in &http_step.extract {
+            if extract.name.is_empty() || extract.json_path.is_some() == extract.header.is_some() {
+                return Err(RegistryError::InvalidHTTPStep);
+            }
+        }
+
+        for http_assert in &http_step.assert {
+            validate_http_assert(http_assert)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_http_assert(
+    http_assert: &ConfigProbeServiceNodeHTTPAssert,
+) -> Result<(), RegistryError> {
+    // Notice: each assertion checks exactly one thing, so that it can be given its own failure \
+    //   status (an assertion may never yield a healthy status upon failure).
+    let checks = [
+        http_assert.status.is_some(),
+        http_assert.header.is_some(),
+        http_assert.json_path.is_some(),
+        http_assert.body_size_above.is_some() || http_assert.body_size_below.is_some(),
+        http_assert.latency_below.is_some(),
+    ];
+
+    if checks.iter().filter(|check| **check).count() != 1 || http_assert.failure == Status::Healthy
+    {
+        return Err(RegistryError::InvalidAssert);
+    }
+
+    // Value matchers only apply to their own check
+    if (http_assert.header_match.is_some() && http_assert.header.is_none())
+        || ((http_assert.json_match.is_some() || http_assert.json_equals.is_some())
+            && http_assert.json_path.is_none())
+        || (http_assert.json_match.is_some() && http_assert.json_equals.is_some())
+    {
+        return Err(RegistryError::InvalidAssert);
+    }
+
+    Ok(())
+}
+
+fn make_dependency(reference: &str) -> ServiceStatesDependency {
+    // Notice: dependency labels are resolved once the dependency is part of the store, \
+    //   see 'label_dependencies()'.
+    ServiceStatesDependency {
+        id: reference.to_owned(),
+        label: reference.to_owned(),
+    }
+}
+
+pub fn make_replica(
+    probe_node: &ServiceStatesProbeNode,
+    replica: Option<&str>,
+    script: Option<&str>,
+) -> Result<(String, ServiceStatesProbeNodeReplica), RegistryError> {
+    let (replica_id, url, script) = match (replica, script) {
+        (Some(replica), None) => {
+            if probe_node.mode != Mode::Poll {
+                return Err(RegistryError::WrongMode);
+            }
+
+            let replica_url =
+                ReplicaURL::parse_from(replica).or(Err(RegistryError::InvalidReplica))?;
+
+            (replica.to_string(), Some(replica_url), None)
+        }
+        (None, Some(script)) => {
+            if probe_node.mode != Mode::Script {
+                return Err(RegistryError::WrongMode);
+            }
+
+            // Notice: script replicas are identified by their index, which must not be reused \
+            //   if a script was removed (hence the highest index being used as a basis).
+            let replica_index = probe_node
+                .replicas
+                .keys()
+                .filter_map(|replica_id| replica_id.parse::<usize>().ok())
+                .max()
+                .map(|replica_index| replica_index + 1)
+                .unwrap_or(0);
+
+            (replica_index.to_string(), None, Some(script.to_owned()))
+        }
+        _ => return Err(RegistryError::InvalidReplica),
+    };
+
+    if probe_node.replicas.contains_key(&replica_id) {
+        return Err(RegistryError::DuplicateReplica);
+    }
+
+    Ok((
+        replica_id,
+        ServiceStatesProbeNodeReplica {
+            status: Status::Healthy,
+            url,
+            script,
+            metrics: ServiceStatesProbeNodeReplicaMetrics::default(),
+            load: None,
+            report: None,
+            changed: None,
+            checked: None,
+        },
+    ))
+}
+
+fn carry_probe(probe: &mut ServiceStatesProbe, previous_probe: &mut ServiceStatesProbe) {
+    probe.status = previous_probe.status;
+    probe.uptime = std::mem::take(&mut previous_probe.uptime);
+    probe.changed = previous_probe.changed;
+
+    for (node_id, previous_node) in previous_probe.nodes.iter_mut() {
+        if let Some(probe_node) = probe.nodes.get_mut(node_id) {
+            carry_node(probe_node, previous_node);
+        }
+    }
+}
+
+fn carry_node(probe_node: &mut ServiceStatesProbeNode, previous_node: &mut ServiceStatesProbeNode) {
+    probe_node.status = previous_node.status;
+    probe_node.uptime = std::mem::take(&mut previous_node.uptime);
+    probe_node.changed = previous_node.changed;
+
+    // Discovered replicas are kept as-is (if discovery is still enabled), until the next \
+    //   discovery run refreshes them.
+    if let (Some(ref mut discover), Some(previous_discover)) =
+        (&mut probe_node.discover, previous_node.discover.take())
+    {
+        for replica_id in previous_discover.replicas {
+            if let Some(replica) = previous_node.replicas.shift_remove(&replica_id) {
+                if !probe_node.replicas.contains_key(&replica_id) {
+                    probe_node.replicas.insert(replica_id.to_owned(), replica);
+
+                    discover.replicas.push(replica_id);
+                }
+            }
+        }
+    }
+
+    // Replicas of 'push' and 'local' nodes are reported dynamically, thus they are kept as-is, \
+    //   while other replicas keep their status if they still exist.
+    if probe_node.mode == previous_node.mode
+        && (probe_node.mode == Mode::Push || probe_node.mode == Mode::Local)
+    {
+        probe_node.replicas = std::mem::take(&mut previous_node.replicas);
+    } else {
+        for (replica_id, previous_replica) in std::mem::take(&mut previous_node.replicas) {
+            if let Some(replica) = probe_node.replicas.get_mut(&replica_id) {
+                replica.status = previous_replica.status;
+                replica.metrics = previous_replica.metrics;
+                replica.changed = previous_replica.changed;
+                replica.checked = previous_replica.checked;
+            }
+        }
+    }
+}
+
+fn is_depended_on(
+    probes: &IndexMap<String, ServiceStatesProbe>,
+    probe_id: &str,
+    node_id: Option<&str>,
+) -> bool {
+    let node_reference = node_id.map(|node_id| format!("{}:{}", probe_id, node_id));
+    let node_reference_prefix = format!("{}:", probe_id);
+
+    probes
+        .iter()
+        .filter(|(other_probe_id, _)| other_probe_id.as_str() != probe_id)
+        .flat_map(|(_, probe)| {
+            probe
+                .depends_on
+                .iter()
+                .chain(probe.nodes.values().flat_map(|node| node.depends_on.iter()))
+        })
+        .chain(
+            // Nodes of the same service may depend on each other (this only matters when \
+            //   removing a single node, as they get removed along with their service otherwise)
+            probes
+                .get(probe_id)
+                .filter(|_| node_id.is_some())
+                .into_iter()
+                .flat_map(|probe| probe.nodes.iter())
+                .filter(|(other_node_id, _)| Some(other_node_id.as_str()) != node_id)
+                .flat_map(|(_, node)| node.depends_on.iter()),
+        )
+        .any(|dependency| match node_reference {
+            Some(ref node_reference) => &dependency.id == node_reference,
+            None => dependency.id == probe_id || dependency.id.starts_with(&node_reference_prefix),
+        })
+}
+
+fn validate_dependencies(
+    probes: &IndexMap<String, ServiceStatesProbe>,
+) -> Result<(), RegistryError> {
+    // Notice: those are the same rules as the ones used when validating the configuration
+    let services: Vec<DependencyService> = probes
+        .iter()
+        .map(|(probe_id, probe)| DependencyService {
+            id: probe_id.as_str(),
+            depends_on: list_dependency_references(&probe.depends_on),
+            nodes: probe
+                .nodes
+                .iter()
+                .map(|(node_id, node)| DependencyNode {
+                    id: node_id.as_str(),
+                    depends_on: list_dependency_references(&node.depends_on),
+                })
+                .collect(),
+        })
+        .collect();
+
+    validate_dependency_graph(&services).map_err(|err| match err {
+        DependencyError::Unknown(..) => RegistryError::UnknownDependency,
+        DependencyError::Cycle(..) => RegistryError::DependencyCycle,
+    })
+}
+
+fn list_dependency_references(dependencies: &[ServiceStatesDependency]) -> Vec<&str> {
+    dependencies
+        .iter()
+        .map(|dependency| dependency.id.as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_http_assert(http_assert: &str) -> ConfigProbeServiceNodeHTTPAssert {
+        toml::from_str(http_assert).unwrap()
+    }
+
+    #[test]
+    fn it_decodes_text_payload() {
+        assert_eq!(
+            decode_payload("PING\r\n", ConfigProbeServiceNodePayloadEncoding::Text).unwrap(),
+            b"PING\r\n"
+        );
+    }
+
+    #[test]
+    fn it_decodes_hex_payload() {
+        assert_eq!(
+            decode_payload("ff FF ff ff 54", ConfigProbeServiceNodePayloadEncoding::Hex).unwrap(),
+            [0xff, 0xff, 0xff, 0xff, 0x54]
+        );
+        assert_eq!(
+            decode_payload("50494e47\n0d0a", ConfigProbeServiceNodePayloadEncoding::Hex).unwrap(),
+            b"PING\r\n"
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_hex_payload() {
+        for payload in ["fff", "f f f", "zz", "0x54", "+f"] {
+            assert!(
+                matches!(
+                    decode_payload(payload, ConfigProbeServiceNodePayloadEncoding::Hex),
+                    Err(RegistryError::InvalidPayload)
+                ),
+                "{}",
+                payload
+            );
+        }
+    }
+
+    #[test]
+    fn it_validates_http_assert_with_one_check() {
+        for http_assert in [
+            "status = [200, 204]",
+            "header = \"content-type\"\nheader_match = \"^application/json\"",
+            "json_path = \"$.status\"\njson_equals = \"ok\"",
+            "json_path = \"$.version\"\njson_match = \"^2\\\\.\"",
+            "body_size_above = 10\nbody_size_below = 1000",
+            "latency_below = 500\nfailure = \"sick\"",
+        ] {
+            assert!(
+                validate_http_assert(&make_http_assert(http_assert)).is_ok(),
+                "{}",
+                http_assert
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_http_assert_without_one_check() {
+        for http_assert in ["failure = \"dead\"", "status = [200]\nlatency_below = 500"] {
+            assert!(
+                matches!(
+                    validate_http_assert(&make_http_assert(http_assert)),
+                    Err(RegistryError::InvalidAssert)
+                ),
+                "{}",
+                http_assert
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_http_assert_with_misplaced_matchers() {
+        for http_assert in [
+            "status = [200]\nheader_match = \"json\"",
+            "status = [200]\njson_equals = \"ok\"",
+            "json_path = \"$.status\"\njson_match = \"ok\"\njson_equals = \"ok\"",
+            "status = [200]\nfailure = \"healthy\"",
+        ] {
+            assert!(
+                matches!(
+                    validate_http_assert(&make_http_assert(http_assert)),
+                    Err(RegistryError::InvalidAssert)
+                ),
+                "{}",
+                http_assert
+            );
+        }
+    }
+}
@@ -7,12 +7,18 @@
 use url::{Host, Url};
 
 #[derive(Serialize, Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ReplicaURL {
     ICMP(String),
     TCP(String, u16),
     SSH(String, u16),
     HTTP(String),
     HTTPS(String),
+    GRPC(String, u16, Option<String>),
+    GRPCS(String, u16, Option<String>),
+    WS(String),
+    WSS(String),
+    UDP(String, u16),
 }
 
 impl ReplicaURL {
@@ -29,6 +35,12 @@ impl ReplicaURL {
                     }
                     _ => Err(()),
                 },
+                "udp" => match (url.host(), url.port(), url.path_segments()) {
+                    (Some(host), Some(port), None) => {
+                        Ok(ReplicaURL::UDP(Self::host_string(host), port))
+                    }
+                    _ => Err(()),
+                },
                 "ssh" => match (url.host(), url.port(), url.path_segments()) {
                     (Some(host), Some(port), None) => {
                         Ok(ReplicaURL::SSH(Self::host_string(host), port))
@@ -37,12 +49,40 @@ impl ReplicaURL {
                 },
                 "http" => Ok(ReplicaURL::HTTP(url.into())),
                 "https" => Ok(ReplicaURL::HTTPS(url.into())),
+                "ws" => Ok(ReplicaURL::WS(url.into())),
+                "wss" => Ok(ReplicaURL::WSS(url.into())),
+                "grpc" => match (url.host(), url.port(), Self::grpc_service(&url)) {
+                    (Some(host), Some(port), Ok(service)) => {
+                        Ok(ReplicaURL::GRPC(Self::host_string(host), port, service))
+                    }
+                    _ => Err(()),
+                },
+                "grpcs" => match (url.host(), url.port(), Self::grpc_service(&url)) {
+                    (Some(host), Some(port), Ok(service)) => {
+                        Ok(ReplicaURL::GRPCS(Self::host_string(host), port, service))
+                    }
+                    _ => Err(()),
+                },
                 _ => Err(()),
             },
             _ => Err(()),
         }
     }
 
+    fn grpc_service(url: &Url) -> Result<Option<String>, ()> {
+        // Acquire the name of the service to check health for (from the URL path, if any), eg. \
+        //   'grpc://localhost:50051/my.package.Service'; an empty name checks the whole server.
+        let service = url.path().trim_start_matches('/');
+
+        if service.contains('/') || url.query().is_some() {
+            Err(())
+        } else if service.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(service.to_string()))
+        }
+    }
+
     fn host_string(host: Host<&str>) -> String {
         // Convert internal host value into string. This is especially useful for IPv6 addresses, \
         //   which we need returned in '::1' format; as they would otherwise be returned in \
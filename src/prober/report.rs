@@ -60,12 +60,13 @@ pub fn handle_load(
             }
 
             // Acquire previous replica status + previous queue load status (follow-up values)
-            let (status, mut metrics, mut load_queue);
+            let (status, changed, mut metrics, mut load_queue);
 
             load_queue = ServiceStatesProbeNodeReplicaLoadQueue::default();
 
             if let Some(ref replica) = node.replicas.get(replica_id) {
                 status = replica.status.to_owned();
+                changed = replica.changed;
                 metrics = replica.metrics.to_owned();
 
                 if let Some(ref replica_load) = replica.load {
@@ -73,6 +74,7 @@ pub fn handle_load(
                 }
             } else {
                 status = Status::Healthy;
+                changed = None;
                 metrics = ServiceStatesProbeNodeReplicaMetrics::default();
             }
 
@@ -99,6 +101,8 @@ pub fn handle_load(
                         time: SystemTime::now(),
                         interval: Duration::from_secs(interval),
                     }),
+                    changed,
+                    checked: Some(SystemTime::now()),
                 },
             );
 
@@ -135,6 +139,13 @@ pub fn handle_health(
                 return Err(HandleHealthError::WrongMode);
             }
 
+            // Acquire previous replica status change time (follow-up value)
+            let changed = match node.replicas.get(replica_id) {
+                Some(replica) if replica.status == *health => replica.changed,
+                Some(_) => Some(SystemTime::now()),
+                None => None,
+            };
+
             // Bump stored replica
             node.replicas.insert(
                 replica_id.to_string(),
@@ -148,6 +159,8 @@ pub fn handle_health(
                         time: SystemTime::now(),
                         interval: Duration::from_secs(interval),
                     }),
+                    changed,
+                    checked: Some(SystemTime::now()),
                 },
             );
 
@@ -4,14 +4,28 @@
 // Copyright: 2018, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
 use indexmap::IndexMap;
+use reqwest::blocking::Client;
 
+use super::aggregate::AggregatePolicy;
+use super::discover::DiscoverSource;
 use super::mode::Mode;
 use super::replica::ReplicaURL;
 use super::status::Status;
-use crate::config::{config::ConfigProbeServiceNodeHTTPMethod, regex::Regex};
+use super::visibility::Visibility;
+use crate::config::config::{
+    ConfigProbeServiceNodeHTTPAssert, ConfigProbeServiceNodeHTTPIPVersion,
+    ConfigProbeServiceNodeHTTPMethod, ConfigProbeServiceNodeHTTPStep,
+    ConfigProbeServiceNodePayloadEncoding, ConfigProbeServiceNodeTCPStartTLSStep,
+    ConfigProbeServiceNodeTCPTLS,
+};
+use crate::config::regex::Regex;
 
 #[derive(Serialize)]
 pub struct ServiceStates {
@@ -26,6 +40,12 @@ pub struct ServiceStatesProbe {
     pub id: String,
     pub label: String,
     pub status: Status,
+    pub visibility: Visibility,
+    pub depends_on: Vec<ServiceStatesDependency>,
+    pub impacted_by: Vec<ServiceStatesDependency>,
+    pub aggregate: AggregatePolicy,
+    pub uptime: ServiceStatesUptime,
+    pub changed: Option<SystemTime>,
     pub nodes: IndexMap<String, ServiceStatesProbeNode>,
 }
 
@@ -34,6 +54,12 @@ pub struct ServiceStatesProbeNode {
     pub status: Status,
     pub label: String,
     pub mode: Mode,
+    pub visibility: Visibility,
+    pub depends_on: Vec<ServiceStatesDependency>,
+    pub impacted_by: Vec<ServiceStatesDependency>,
+    pub aggregate: AggregatePolicy,
+    pub uptime: ServiceStatesUptime,
+    pub changed: Option<SystemTime>,
     pub replicas: IndexMap<String, ServiceStatesProbeNodeReplica>,
     #[serde(default)]
     #[serde(with = "http_serde::header_map")]
@@ -41,10 +67,32 @@ pub struct ServiceStatesProbeNode {
     pub http_method: Option<ConfigProbeServiceNodeHTTPMethod>,
     pub http_body: Option<String>,
     pub http_body_healthy_match: Option<Regex>,
+    pub http_assert: Vec<ConfigProbeServiceNodeHTTPAssert>,
+    pub http_steps: Vec<ConfigProbeServiceNodeHTTPStep>,
+    pub http_client: Option<ServiceStatesProbeNodeHTTPClient>,
+    pub websocket_message: Option<String>,
+    pub websocket_reply_healthy_match: Option<Regex>,
+    pub udp_payload: Option<Vec<u8>>,
+    pub udp_payload_encoding: ConfigProbeServiceNodePayloadEncoding,
+    pub udp_reply_healthy_match: Option<Regex>,
+    pub tcp: Option<ServiceStatesProbeNodeTCP>,
     pub reveal_replica_name: bool,
     pub link_url: Option<String>,
     pub link_label: Option<String>,
     pub rabbitmq: Option<ServiceStatesProbeNodeRabbitMQ>,
+    pub discover: Option<ServiceStatesProbeNodeDiscover>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ServiceStatesDependency {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct ServiceStatesUptime {
+    pub up: u64,
+    pub total: u64,
 }
 
 #[derive(Serialize)]
@@ -55,6 +103,8 @@ pub struct ServiceStatesProbeNodeReplica {
     pub metrics: ServiceStatesProbeNodeReplicaMetrics,
     pub load: Option<ServiceStatesProbeNodeReplicaLoad>,
     pub report: Option<ServiceStatesProbeNodeReplicaReport>,
+    pub changed: Option<SystemTime>,
+    pub checked: Option<SystemTime>,
 }
 
 #[derive(Serialize, Clone)]
@@ -64,6 +114,44 @@ pub struct ServiceStatesProbeNodeRabbitMQ {
     pub queue_nack_dead_above: Option<u32>,
 }
 
+#[derive(Serialize, Clone)]
+pub struct ServiceStatesProbeNodeDiscover {
+    pub source: DiscoverSource,
+    pub template: Option<String>,
+    pub replicas: Vec<String>,
+    pub modified: Option<SystemTime>,
+}
+
+pub type ServiceStatesProbeNodeHTTPSNIClients = HashMap<String, (Vec<SocketAddr>, Client)>;
+
+#[derive(Serialize, Clone)]
+pub struct ServiceStatesProbeNodeHTTPClient {
+    pub redirects: usize,
+    pub tls_ca: Option<PathBuf>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub tls_insecure: bool,
+    pub tls_sni: Option<String>,
+    pub ip_version: Option<ConfigProbeServiceNodeHTTPIPVersion>,
+
+    #[serde(skip)]
+    pub client: Option<Client>,
+
+    #[serde(skip)]
+    pub sni_clients: Arc<RwLock<ServiceStatesProbeNodeHTTPSNIClients>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ServiceStatesProbeNodeTCP {
+    pub banner_healthy_match: Option<Regex>,
+    pub payload: Option<Vec<u8>>,
+    pub payload_encoding: ConfigProbeServiceNodePayloadEncoding,
+    pub reply_healthy_match: Option<Regex>,
+    pub tls: Option<ConfigProbeServiceNodeTCPTLS>,
+    pub starttls: Vec<ConfigProbeServiceNodeTCPStartTLSStep>,
+    pub tls_insecure: bool,
+}
+
 #[derive(Serialize, Clone, Default)]
 pub struct ServiceStatesProbeNodeReplicaMetrics {
     pub latency: Option<u64>,
@@ -83,7 +171,7 @@ pub struct ServiceStatesProbeNodeReplicaMetricsRabbitMQ {
     pub queue_nack: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ServiceStatesProbeNodeReplicaLoad {
     pub cpu: f32,
     pub ram: f32,
@@ -108,3 +196,23 @@ pub struct ServiceStatesNotifier {
     pub reminder_backoff_counter: u16,
     pub reminder_ignore_until: Option<SystemTime>,
 }
+
+impl ServiceStatesUptime {
+    pub fn record(&mut self, status: Status) {
+        // Notice: sick services are still considered up, as they are able to serve requests \
+        //   (only dead services count as downtime).
+        if status != Status::Dead {
+            self.up += 1;
+        }
+
+        self.total += 1;
+    }
+
+    pub fn percent(&self) -> Option<f64> {
+        if self.total > 0 {
+            Some((self.up as f64 / self.total as f64) * 100.0)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,522 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::time::SystemTime;
+
+use time;
+use time::format_description::FormatItem;
+
+use indexmap::IndexMap;
+
+use super::mode::Mode;
+use super::states::{
+    ServiceStates, ServiceStatesDependency, ServiceStatesProbeNodeReplica,
+    ServiceStatesProbeNodeReplicaLoad, ServiceStatesProbeNodeReplicaMetrics, ServiceStatesUptime,
+};
+use super::status::Status;
+
+lazy_static! {
+    static ref REPORT_TIME_FORMATTER: Vec<FormatItem<'static>> =
+        time::format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second] UTC")
+            .expect("invalid time format");
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Visibility {
+    #[serde(rename = "public")]
+    Public,
+
+    #[serde(rename = "internal")]
+    Internal,
+
+    #[serde(rename = "hidden")]
+    Hidden,
+}
+
+#[derive(Serialize)]
+pub struct ServiceStatesView<'a> {
+    pub status: Status,
+    pub date: &'a Option<String>,
+    pub probes: IndexMap<&'a str, ServiceStatesViewProbe<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ServiceStatesViewProbe<'a> {
+    pub id: &'a str,
+    pub label: &'a str,
+    pub status: Status,
+    pub depends_on: Vec<&'a ServiceStatesDependency>,
+    pub impacted_by: Vec<&'a ServiceStatesDependency>,
+    pub uptime: &'a ServiceStatesUptime,
+    pub changed: Option<SystemTime>,
+    pub nodes: IndexMap<&'a str, ServiceStatesViewProbeNode<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ServiceStatesViewProbeNode<'a> {
+    pub status: Status,
+    pub label: &'a str,
+    pub mode: &'a Mode,
+    pub depends_on: Vec<&'a ServiceStatesDependency>,
+    pub impacted_by: Vec<&'a ServiceStatesDependency>,
+    pub uptime: &'a ServiceStatesUptime,
+    pub changed: Option<SystemTime>,
+    pub replicas: IndexMap<&'a str, ServiceStatesViewProbeNodeReplica<'a>>,
+    pub reveal_replica_name: bool,
+    pub link_url: &'a Option<String>,
+    pub link_label: &'a Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ServiceStatesViewProbeNodeReplica<'a> {
+    pub status: Status,
+    pub metrics: &'a ServiceStatesProbeNodeReplicaMetrics,
+    pub changed: Option<SystemTime>,
+    pub checked: Option<SystemTime>,
+    pub details: Option<ServiceStatesViewProbeNodeReplicaDetails<'a>>,
+}
+
+pub struct ServiceStatesSummary {
+    pub status: Status,
+    pub probes: Vec<ServiceStatesSummaryProbe>,
+}
+
+pub struct ServiceStatesSummaryProbe {
+    pub id: String,
+    pub label: String,
+    pub status: Status,
+    pub nodes: Vec<ServiceStatesSummaryProbeNode>,
+}
+
+pub struct ServiceStatesSummaryProbeNode {
+    pub id: String,
+    pub status: Status,
+    pub reveal_replica_name: bool,
+    pub replicas: Vec<(String, Status)>,
+}
+
+#[derive(Serialize)]
+pub struct ServiceStatesViewProbeNodeReplicaDetails<'a> {
+    pub script: &'a Option<String>,
+    pub load: &'a Option<ServiceStatesProbeNodeReplicaLoad>,
+    pub report_time: Option<String>,
+    pub report_interval: Option<u64>,
+}
+
+impl Visibility {
+    pub fn visible_in(&self, scope: Visibility) -> bool {
+        match self {
+            Visibility::Public => true,
+            Visibility::Internal => scope == Visibility::Internal,
+            Visibility::Hidden => false,
+        }
+    }
+}
+
+impl ServiceStates {
+    pub fn view(&self, scope: Visibility, detailed: bool) -> ServiceStatesView<'_> {
+        let mut view = ServiceStatesView {
+            status: Status::Healthy,
+            date: &self.date,
+            probes: IndexMap::new(),
+        };
+
+        for (probe_id, probe) in self.probes.iter() {
+            if !probe.visibility.visible_in(scope) {
+                continue;
+            }
+
+            let mut view_probe = ServiceStatesViewProbe {
+                id: &probe.id,
+                label: &probe.label,
+                status: Status::Healthy,
+                depends_on: self.filter_dependencies(&probe.depends_on, scope),
+                impacted_by: self.filter_dependencies(&probe.impacted_by, scope),
+                uptime: &probe.uptime,
+                changed: probe.changed,
+                nodes: IndexMap::new(),
+            };
+
+            let (mut dead_nodes, mut healthy_nodes) = (0, 0);
+
+            for (node_id, node) in probe.nodes.iter() {
+                if !node.visibility.visible_in(scope) {
+                    continue;
+                }
+
+                view_probe.status = worst_status(view_probe.status, node.status);
+
+                match node.status {
+                    Status::Dead => dead_nodes += 1,
+                    Status::Healthy => healthy_nodes += 1,
+                    _ => {}
+                }
+
+                view_probe.nodes.insert(
+                    node_id,
+                    ServiceStatesViewProbeNode {
+                        status: node.status,
+                        label: &node.label,
+                        mode: &node.mode,
+                        depends_on: self.filter_dependencies(&node.depends_on, scope),
+                        impacted_by: self.filter_dependencies(&node.impacted_by, scope),
+                        uptime: &node.uptime,
+                        changed: node.changed,
+                        replicas: node
+                            .replicas
+                            .iter()
+                            .map(|(replica_id, replica)| {
+                                (replica_id.as_str(), view_replica(replica, detailed))
+                            })
+                            .collect(),
+                        reveal_replica_name: node.reveal_replica_name,
+                        link_url: &node.link_url,
+                        link_label: &node.link_label,
+                    },
+                );
+            }
+
+            // Skip services without any visible node
+            if view_probe.nodes.is_empty() {
+                continue;
+            }
+
+            // Notice: if some nodes are not visible, the service status is aggregated from its \
+            //   visible nodes only, so that non-visible failures do not leak through the service \
+            //   status (and the general status).
+            if view_probe.nodes.len() == probe.nodes.len() {
+                view_probe.status = probe.status;
+            } else {
+                view_probe.status = probe.aggregate.resolve(
+                    view_probe.status,
+                    view_probe.nodes.len(),
+                    dead_nodes,
+                    healthy_nodes,
+                );
+
+                view_probe.changed = view_probe
+                    .nodes
+                    .values()
+                    .filter_map(|view_node| view_node.changed)
+                    .max();
+            }
+
+            view.status = worst_status(view.status, view_probe.status);
+
+            view.probes.insert(probe_id, view_probe);
+        }
+
+        view
+    }
+
+    fn filter_dependencies<'a>(
+        &self,
+        dependencies: &'a [ServiceStatesDependency],
+        scope: Visibility,
+    ) -> Vec<&'a ServiceStatesDependency> {
+        dependencies
+            .iter()
+            .filter(|dependency| {
+                let mut reference_parts = dependency.id.splitn(2, ':');
+
+                match (
+                    reference_parts
+                        .next()
+                        .and_then(|probe_id| self.probes.get(probe_id)),
+                    reference_parts.next(),
+                ) {
+                    (Some(probe), Some(node_id)) => {
+                        probe.visibility.visible_in(scope)
+                            && probe
+                                .nodes
+                                .get(node_id)
+                                .map(|node| node.visibility.visible_in(scope))
+                                .unwrap_or(false)
+                    }
+                    (Some(probe), None) => probe.visibility.visible_in(scope),
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+}
+
+impl ServiceStatesView<'_> {
+    pub fn summarize(&self) -> ServiceStatesSummary {
+        // Notice: summaries own their values, so that they can be used once the states store \
+        //   lock is released (unlike views, which borrow from the states).
+        ServiceStatesSummary {
+            status: self.status,
+            probes: self
+                .probes
+                .iter()
+                .map(|(probe_id, probe)| ServiceStatesSummaryProbe {
+                    id: probe_id.to_string(),
+                    label: probe.label.to_string(),
+                    status: probe.status,
+                    nodes: probe
+                        .nodes
+                        .iter()
+                        .map(|(node_id, node)| ServiceStatesSummaryProbeNode {
+                            id: node_id.to_string(),
+                            status: node.status,
+                            reveal_replica_name: node.reveal_replica_name,
+                            replicas: node
+                                .replicas
+                                .iter()
+                                .map(|(replica_id, replica)| {
+                                    (replica_id.to_string(), replica.status)
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn view_replica(
+    replica: &ServiceStatesProbeNodeReplica,
+    detailed: bool,
+) -> ServiceStatesViewProbeNodeReplica<'_> {
+    ServiceStatesViewProbeNodeReplica {
+        status: replica.status,
+        metrics: &replica.metrics,
+        changed: replica.changed,
+        checked: replica.checked,
+
+        // Notice: replica details are only included in detailed views, as they may reveal \
+        //   sensitive information (eg. scripts source code).
+        details: if detailed {
+            Some(ServiceStatesViewProbeNodeReplicaDetails {
+                script: &replica.script,
+                load: &replica.load,
+                report_time: replica.report.as_ref().and_then(|report| {
+                    time::OffsetDateTime::from(report.time)
+                        .format(&REPORT_TIME_FORMATTER)
+                        .ok()
+                }),
+                report_interval: replica
+                    .report
+                    .as_ref()
+                    .map(|report| report.interval.as_secs()),
+            })
+        } else {
+            None
+        },
+    }
+}
+
+fn worst_status(status: Status, other_status: Status) -> Status {
+    match (status, other_status) {
+        (Status::Dead, _) | (_, Status::Dead) => Status::Dead,
+        (Status::Sick, _) | (_, Status::Sick) => Status::Sick,
+        _ => Status::Healthy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config::ConfigProbe;
+    use crate::prober::registry::make_probe;
+    use crate::prober::states::ServiceStatesNotifier;
+
+    fn make_states(services: &str, statuses: &[(&str, &str, Status)]) -> ServiceStates {
+        let config: ConfigProbe = toml::from_str(services).unwrap();
+
+        let mut states = ServiceStates {
+            status: Status::Healthy,
+            date: None,
+            probes: config
+                .service
+                .iter()
+                .map(|service| (service.id.to_owned(), make_probe(service).unwrap()))
+                .collect(),
+            notifier: ServiceStatesNotifier {
+                reminder_escalate_counter: 0,
+                reminder_backoff_counter: 0,
+                reminder_ignore_until: None,
+            },
+        };
+
+        // Apply node statuses, and aggregate service statuses from all nodes
+        for (probe_id, node_id, status) in statuses {
+            let probe = states.probes.get_mut(*probe_id).unwrap();
+
+            probe.nodes.get_mut(*node_id).unwrap().status = *status;
+            probe.status = worst_status(probe.status, *status);
+
+            states.status = worst_status(states.status, *status);
+        }
+
+        states
+    }
+
+    #[test]
+    fn it_filters_hidden_and_internal_nodes() {
+        let states = make_states(
+            r#"
+            [[service]]
+            id = "api"
+            label = "API"
+
+            [[service.node]]
+            id = "public"
+            label = "Public"
+            mode = "push"
+
+            [[service.node]]
+            id = "internal"
+            label = "Internal"
+            mode = "push"
+            visibility = "internal"
+
+            [[service.node]]
+            id = "hidden"
+            label = "Hidden"
+            mode = "push"
+            visibility = "hidden"
+
+            [[service]]
+            id = "backoffice"
+            label = "Backoffice"
+            visibility = "internal"
+
+            [[service.node]]
+            id = "web"
+            label = "Web"
+            mode = "push"
+            "#,
+            &[],
+        );
+
+        let public_view = states.view(Visibility::Public, false);
+        let internal_view = states.view(Visibility::Internal, false);
+
+        assert_eq!(
+            public_view.probes.keys().copied().collect::<Vec<_>>(),
+            ["api"]
+        );
+        assert_eq!(
+            public_view.probes["api"]
+                .nodes
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            ["public"]
+        );
+
+        assert_eq!(
+            internal_view.probes.keys().copied().collect::<Vec<_>>(),
+            ["api", "backoffice"]
+        );
+        assert_eq!(
+            internal_view.probes["api"]
+                .nodes
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            ["public", "internal"]
+        );
+    }
+
+    #[test]
+    fn it_skips_services_without_visible_nodes() {
+        let states = make_states(
+            r#"
+            [[service]]
+            id = "jobs"
+            label = "Jobs"
+
+            [[service.node]]
+            id = "worker"
+            label = "Worker"
+            mode = "push"
+            visibility = "hidden"
+            "#,
+            &[("jobs", "worker", Status::Dead)],
+        );
+
+        let view = states.view(Visibility::Internal, false);
+
+        assert!(view.probes.is_empty());
+        assert_eq!(view.status, Status::Healthy);
+    }
+
+    #[test]
+    fn it_recomputes_status_from_visible_nodes() {
+        let states = make_states(
+            r#"
+            [[service]]
+            id = "api"
+            label = "API"
+
+            [[service.node]]
+            id = "public"
+            label = "Public"
+            mode = "push"
+
+            [[service.node]]
+            id = "internal"
+            label = "Internal"
+            mode = "push"
+            visibility = "internal"
+            "#,
+            &[
+                ("api", "public", Status::Sick),
+                ("api", "internal", Status::Dead),
+            ],
+        );
+
+        let public_view = states.view(Visibility::Public, false);
+        let internal_view = states.view(Visibility::Internal, false);
+
+        // Internal failures do not leak through the public service status
+        assert_eq!(public_view.probes["api"].status, Status::Sick);
+        assert_eq!(public_view.status, Status::Sick);
+
+        assert_eq!(internal_view.probes["api"].status, Status::Dead);
+        assert_eq!(internal_view.status, Status::Dead);
+    }
+
+    #[test]
+    fn it_recomputes_status_with_aggregate_policy() {
+        let states = make_states(
+            r#"
+            [[service]]
+            id = "api"
+            label = "API"
+            aggregate = "quorum"
+            aggregate_quorum = 50
+
+            [[service.node]]
+            id = "a"
+            label = "A"
+            mode = "push"
+
+            [[service.node]]
+            id = "b"
+            label = "B"
+            mode = "push"
+
+            [[service.node]]
+            id = "c"
+            label = "C"
+            mode = "push"
+            visibility = "hidden"
+            "#,
+            &[("api", "a", Status::Dead), ("api", "c", Status::Dead)],
+        );
+
+        // 1 node out of the 2 visible nodes is up, which reaches the quorum
+        assert_eq!(
+            states.view(Visibility::Public, false).probes["api"].status,
+            Status::Sick
+        );
+    }
+}
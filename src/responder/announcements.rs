@@ -6,6 +6,7 @@
 
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::SystemTime;
 use time;
 use time::format_description::FormatItem;
 
@@ -30,4 +31,7 @@ pub struct Announcement {
     pub title: String,
     pub text: String,
     pub date: Option<String>,
+
+    #[serde(skip)]
+    pub time: SystemTime,
 }
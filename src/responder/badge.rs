@@ -0,0 +1,170 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use tera::escape_html;
+
+use crate::prober::status::Status;
+use crate::prober::visibility::ServiceStatesView;
+use crate::APP_CONF;
+
+const LABEL_MAXIMUM_LENGTH: usize = 64;
+const TEXT_PADDING: u32 = 6;
+
+pub struct Badge {
+    pub label: String,
+    pub status: Status,
+    pub uptime: Option<f64>,
+}
+
+impl Badge {
+    pub fn resolve(
+        states: &ServiceStatesView,
+        probe_id: Option<&str>,
+        node_id: Option<&str>,
+    ) -> Option<Self> {
+        match (probe_id, node_id) {
+            (Some(probe_id), Some(node_id)) => {
+                let node = states.probes.get(probe_id)?.nodes.get(node_id)?;
+
+                Some(Badge {
+                    label: node.label.to_owned(),
+                    status: node.status,
+                    uptime: node.uptime.percent(),
+                })
+            }
+            (Some(probe_id), None) => {
+                let probe = states.probes.get(probe_id)?;
+
+                Some(Badge {
+                    label: probe.label.to_owned(),
+                    status: probe.status,
+                    uptime: probe.uptime.percent(),
+                })
+            }
+            _ => Some(Badge {
+                label: APP_CONF.branding.page_title.to_owned(),
+                status: states.status,
+                uptime: None,
+            }),
+        }
+    }
+
+    pub fn relabel(&mut self, label: &str) {
+        self.label = label.chars().take(LABEL_MAXIMUM_LENGTH).collect();
+    }
+
+    pub fn message(&self) -> String {
+        if let Some(uptime) = self.uptime {
+            format!("{} · {}", self.status.as_str(), format_uptime(uptime))
+        } else {
+            self.status.as_str().to_string()
+        }
+    }
+
+    pub fn color(&self) -> &'static str {
+        // Notice: use the same colors as pre-rendered badge images
+        match self.status {
+            Status::Healthy => "0EB033",
+            Status::Sick => "F18000",
+            Status::Dead => "E10000",
+        }
+    }
+
+    pub fn render_svg(&self) -> String {
+        let message = self.message();
+
+        let (label_width, message_width) = (
+            measure_text(&self.label) + 2 * TEXT_PADDING,
+            measure_text(&message) + 2 * TEXT_PADDING,
+        );
+        let width = label_width + message_width;
+
+        let (label, message) = (escape_html(&self.label), escape_html(&message));
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" \
+            role=\"img\" aria-label=\"{label}: {message}\">\
+            <title>{label}: {message}</title>\
+            <linearGradient id=\"s\" x2=\"0\" y2=\"100%\">\
+            <stop offset=\"0\" stop-color=\"#BBB\" stop-opacity=\".1\"/>\
+            <stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>\
+            <clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"3\" fill=\"#FFF\"/>\
+            </clipPath>\
+            <g clip-path=\"url(#r)\">\
+            <rect width=\"{label_width}\" height=\"20\" fill=\"#555\"/>\
+            <rect x=\"{label_width}\" width=\"{message_width}\" height=\"20\" fill=\"#{color}\"/>\
+            <rect width=\"{width}\" height=\"20\" fill=\"url(#s)\"/></g>\
+            <g fill=\"#FFF\" text-anchor=\"middle\" \
+            font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\
+            <text x=\"{label_center}\" y=\"14\">{label}</text>\
+            <text x=\"{message_center}\" y=\"14\">{message}</text></g></svg>",
+            width = width,
+            label_width = label_width,
+            message_width = message_width,
+            label_center = label_width / 2,
+            message_center = label_width + message_width / 2,
+            color = self.color(),
+            label = label,
+            message = message
+        )
+    }
+}
+
+fn format_uptime(uptime: f64) -> String {
+    // Notice: round down, so that an uptime which is not perfect never shows as 100%
+    let uptime = (uptime * 100.0).floor() / 100.0;
+
+    if uptime >= 100.0 {
+        "100%".to_string()
+    } else {
+        format!("{:.2}%", uptime)
+    }
+}
+
+fn measure_text(text: &str) -> u32 {
+    // Approximate text width, using average glyph widths of an 11px Verdana font (the badge \
+    //   image is not rendered server-side, so an exact measurement is not possible)
+    text.chars()
+        .map(|character| match character {
+            'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '!' | '\'' | '|' | '·' => 4,
+            ' ' | 'f' | 'r' | 't' | '(' | ')' | '[' | ']' | '-' => 5,
+            'm' | 'w' | 'M' | 'W' | '%' | '@' => 11,
+            'A'..='Z' => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_uptime() {
+        assert_eq!(format_uptime(100.0), "100%");
+        assert_eq!(format_uptime(99.5), "99.50%");
+        assert_eq!(format_uptime(0.0), "0.00%");
+    }
+
+    #[test]
+    fn it_formats_uptime_rounded_down() {
+        assert_eq!(format_uptime(99.999), "99.99%");
+        assert_eq!(format_uptime(98.456), "98.45%");
+    }
+
+    #[test]
+    fn it_measures_text() {
+        assert_eq!(measure_text(""), 0);
+        assert_eq!(measure_text("up"), 14);
+        assert_eq!(measure_text("100%"), 32);
+        assert_eq!(measure_text("Status"), 8 + 5 + 7 + 5 + 7 + 7);
+    }
+
+    #[test]
+    fn it_measures_narrow_text_as_shorter() {
+        assert!(measure_text("illi") < measure_text("mwmw"));
+    }
+}
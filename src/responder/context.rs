@@ -9,7 +9,7 @@ use time;
 use url_serde::SerdeUrl;
 
 use super::announcements::Announcement;
-use crate::prober::states::ServiceStates;
+use crate::prober::visibility::ServiceStatesView;
 use crate::APP_CONF;
 
 const LOGO_EXTENSION_SPLIT_SPAN: usize = 4;
@@ -24,9 +24,11 @@ lazy_static! {
         icon_mime: ImageMime::guess_from(APP_CONF.branding.icon_url.as_str()),
         logo_color: APP_CONF.branding.logo_color.to_owned(),
         logo_url: APP_CONF.branding.logo_url.to_owned(),
+        page_url: APP_CONF.branding.page_url.to_owned(),
         website_url: APP_CONF.branding.website_url.to_owned(),
         support_url: APP_CONF.branding.support_url.to_owned(),
         custom_html: APP_CONF.branding.custom_html.to_owned(),
+        subscribe: APP_CONF.subscribers.is_some(),
     };
     pub static ref INDEX_ENVIRONMENT: IndexContextEnvironment = IndexContextEnvironment::default();
 }
@@ -74,12 +76,84 @@ impl Default for IndexContextEnvironment {
 
 #[derive(Serialize)]
 pub struct IndexContext<'a, 'b> {
-    pub states: &'a ServiceStates,
+    pub states: ServiceStatesView<'a>,
     pub announcements: &'a Vec<Announcement>,
+    pub private: bool,
     pub environment: &'a IndexContextEnvironment,
     pub config: &'b IndexContextConfig,
 }
 
+#[derive(Serialize)]
+pub enum SubscribeOutcome {
+    #[serde(rename = "pending")]
+    Pending,
+
+    #[serde(rename = "confirm_prompt")]
+    ConfirmPrompt,
+
+    #[serde(rename = "confirmed")]
+    Confirmed,
+
+    #[serde(rename = "unsubscribe_prompt")]
+    UnsubscribePrompt,
+
+    #[serde(rename = "unsubscribed")]
+    Unsubscribed,
+
+    #[serde(rename = "invalid")]
+    Invalid,
+
+    #[serde(rename = "unknown")]
+    Unknown,
+
+    #[serde(rename = "full")]
+    Full,
+}
+
+#[derive(Serialize)]
+pub struct SubscribeContext<'a> {
+    pub services: Vec<SubscribeContextService<'a>>,
+    pub channels: SubscribeContextChannels,
+    pub outcome: Option<SubscribeOutcome>,
+    pub token: Option<&'a str>,
+    pub environment: &'a IndexContextEnvironment,
+    pub config: &'a IndexContextConfig,
+}
+
+#[derive(Serialize)]
+pub struct SubscribeContextService<'a> {
+    pub id: &'a str,
+    pub label: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct SubscribeContextChannels {
+    pub email: bool,
+    pub webhook: bool,
+}
+
+#[derive(Serialize)]
+pub struct FeedContext<'a> {
+    pub feed_url: String,
+    pub updated: FeedContextTime,
+    pub entries: Vec<FeedContextEntry>,
+    pub config: &'a IndexContextConfig,
+}
+
+#[derive(Serialize)]
+pub struct FeedContextEntry {
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    pub updated: FeedContextTime,
+}
+
+#[derive(Serialize)]
+pub struct FeedContextTime {
+    pub rfc3339: String,
+    pub rfc2822: String,
+}
+
 #[derive(Serialize)]
 pub struct IndexContextConfig {
     pub runtime_version: String,
@@ -90,9 +164,11 @@ pub struct IndexContextConfig {
     pub icon_mime: ImageMime,
     pub logo_color: String,
     pub logo_url: SerdeUrl,
+    pub page_url: SerdeUrl,
     pub website_url: SerdeUrl,
     pub support_url: SerdeUrl,
     pub custom_html: Option<String>,
+    pub subscribe: bool,
 }
 
 #[derive(Serialize)]
@@ -0,0 +1,187 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use actix_web::web::Bytes;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, Sender};
+use tokio::time;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio_stream::{self as stream, Stream, StreamExt};
+
+use super::announcements::Announcement;
+use crate::prober::status::Status;
+use crate::prober::visibility::ServiceStatesSummary;
+
+const EVENTS_CHANNEL_CAPACITY: usize = 256;
+const EVENTS_RETRY_MILLISECONDS: u64 = 5000;
+const EVENTS_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(20);
+
+lazy_static! {
+    pub static ref STORE: Arc<RwLock<Store>> = Arc::new(RwLock::new(Store {
+        statuses: HashMap::new(),
+    }));
+    static ref CHANNEL: Sender<Bytes> = broadcast::channel(EVENTS_CHANNEL_CAPACITY).0;
+}
+
+pub struct Store {
+    statuses: HashMap<String, Status>,
+}
+
+#[derive(Serialize)]
+struct EventStatus<'a> {
+    probe: Option<&'a str>,
+    node: Option<&'a str>,
+    replica: Option<usize>,
+    replica_name: Option<&'a str>,
+    status: Status,
+    previous: Status,
+}
+
+#[derive(Serialize)]
+struct EventAnnouncementInsert<'a> {
+    id: &'a str,
+    title: &'a str,
+    text: &'a str,
+    date: &'a Option<String>,
+}
+
+#[derive(Serialize)]
+struct EventAnnouncementRetract<'a> {
+    id: &'a str,
+}
+
+pub fn subscribe() -> impl Stream<Item = Result<Bytes, Infallible>> {
+    // Notice: subscribers that lag behind have missed some events, therefore they get notified \
+    //   that they should re-synchronize their whole state (eg. reload the status page).
+    let events = BroadcastStream::new(CHANNEL.subscribe()).map(|event| match event {
+        Ok(event) => event,
+        Err(_) => format_event("resync", &()),
+    });
+
+    // Send comments at a regular interval, so that idle connections are kept open by proxies
+    let keepalive = IntervalStream::new(time::interval(EVENTS_KEEPALIVE_INTERVAL))
+        .map(|_| Bytes::from_static(b": keepalive\n\n"));
+
+    stream::once(Bytes::from(format!(
+        "retry: {}\n\n",
+        EVENTS_RETRY_MILLISECONDS
+    )))
+    .chain(events.merge(keepalive))
+    .map(Ok)
+}
+
+pub fn publish_states(states: &ServiceStatesSummary) {
+    let mut store = STORE.write().unwrap();
+
+    // Notice: statuses are published from the public view of services (as the stream is \
+    //   public), which is why changes are detected there, and not in the aggregator itself.
+    publish_status(&mut store, None, None, None, states.status);
+
+    for probe in states.probes.iter() {
+        publish_status(&mut store, Some(&probe.id), None, None, probe.status);
+
+        for node in probe.nodes.iter() {
+            publish_status(
+                &mut store,
+                Some(&probe.id),
+                Some(&node.id),
+                None,
+                node.status,
+            );
+
+            for (replica_index, (replica_id, replica_status)) in node.replicas.iter().enumerate() {
+                // Replica names are not revealed, unless the node is configured to do so
+                let replica_name = if node.reveal_replica_name {
+                    Some(replica_id.as_str())
+                } else {
+                    None
+                };
+
+                publish_status(
+                    &mut store,
+                    Some(&probe.id),
+                    Some(&node.id),
+                    Some((replica_index + 1, replica_id, replica_name)),
+                    *replica_status,
+                );
+            }
+        }
+    }
+}
+
+pub fn publish_announcement_insert(announcement: &Announcement) {
+    publish(
+        "announcement_insert",
+        &EventAnnouncementInsert {
+            id: &announcement.id,
+            title: &announcement.title,
+            text: &announcement.text,
+            date: &announcement.date,
+        },
+    );
+}
+
+pub fn publish_announcement_retract(announcement_id: &str) {
+    publish(
+        "announcement_retract",
+        &EventAnnouncementRetract {
+            id: announcement_id,
+        },
+    );
+}
+
+fn publish_status(
+    store: &mut Store,
+    probe_id: Option<&str>,
+    node_id: Option<&str>,
+    replica: Option<(usize, &str, Option<&str>)>,
+    status: Status,
+) {
+    let path = [
+        probe_id.unwrap_or(""),
+        node_id.unwrap_or(""),
+        replica.map(|replica| replica.1).unwrap_or(""),
+    ]
+    .join(":");
+
+    // Publish status change? (the first status seen for a path is a baseline)
+    if let Some(previous) = store.statuses.insert(path, status) {
+        if previous != status {
+            publish(
+                "status",
+                &EventStatus {
+                    probe: probe_id,
+                    node: node_id,
+                    replica: replica.map(|replica| replica.0),
+                    replica_name: replica.and_then(|replica| replica.2),
+                    status,
+                    previous,
+                },
+            );
+        }
+    }
+}
+
+fn publish<T: Serialize>(name: &str, data: &T) {
+    debug!("publishing stream event: {}", name);
+
+    // Notice: sending fails if there are no subscribers, which can be safely ignored
+    let _ = CHANNEL.send(format_event(name, data));
+}
+
+fn format_event<T: Serialize>(name: &str, data: &T) -> Bytes {
+    Bytes::from(format!(
+        "event: {}\ndata: {}\n\n",
+        name,
+        serde_json::to_string(data).unwrap_or_default()
+    ))
+}
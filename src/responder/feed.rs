@@ -0,0 +1,127 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::cmp::Reverse;
+use std::time::SystemTime;
+
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+
+use super::announcements::STORE as ANNOUNCEMENTS_STORE;
+use super::context::{FeedContext, FeedContextEntry, FeedContextTime, INDEX_CONFIG};
+use crate::aggregator::history::STORE as HISTORY_STORE;
+use crate::APP_CONF;
+
+const FEED_ENTRIES_MAXIMUM: usize = 50;
+
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    pub fn template(&self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "feed.atom.tera",
+            FeedFormat::Rss => "feed.rss.tera",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+
+    fn path(&self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "feed.atom",
+            FeedFormat::Rss => "feed.rss",
+        }
+    }
+}
+
+impl FeedContextTime {
+    fn from(time: SystemTime) -> Self {
+        let time = time::OffsetDateTime::from(time);
+
+        FeedContextTime {
+            rfc3339: time.format(&Rfc3339).unwrap_or_default(),
+            rfc2822: time.format(&Rfc2822).unwrap_or_default(),
+        }
+    }
+}
+
+pub fn make_context(format: &FeedFormat) -> FeedContext<'static> {
+    let mut entries = Vec::new();
+
+    // Notice acquire locks in blocks to release them ASAP (ie. one after the other)
+    {
+        let announcements_store = ANNOUNCEMENTS_STORE.read().unwrap();
+
+        for announcement in announcements_store.announcements.iter() {
+            entries.push((
+                announcement.time,
+                announcement.id.to_owned(),
+                announcement.title.to_owned(),
+                announcement.text.to_owned(),
+            ));
+        }
+    }
+
+    let since = {
+        let history_store = HISTORY_STORE.read().unwrap();
+
+        for transition in history_store.transitions.iter() {
+            entries.push((
+                transition.time,
+                transition.id.to_owned(),
+                format!(
+                    "{} is now {}",
+                    transition.probe_label,
+                    transition.to.as_str()
+                ),
+                format!(
+                    "The status of {} changed from {} to {}.",
+                    transition.probe_label,
+                    transition.from.as_str(),
+                    transition.to.as_str()
+                ),
+            ));
+        }
+
+        history_store.since
+    };
+
+    // List most recent entries first
+    entries.sort_by_key(|entry| Reverse(entry.0));
+    entries.truncate(FEED_ENTRIES_MAXIMUM);
+
+    FeedContext {
+        feed_url: format!(
+            "{}/{}",
+            APP_CONF.branding.page_url.as_str().trim_end_matches('/'),
+            format.path()
+        ),
+
+        // Notice: the feed is last updated when its most recent entry was published, or when \
+        //   Vigil started if there are no entries yet (this must not be the current time, as \
+        //   feed readers would otherwise believe that the feed changes on every fetch).
+        updated: FeedContextTime::from(entries.first().map(|entry| entry.0).unwrap_or(since)),
+
+        entries: entries
+            .into_iter()
+            .map(|(time, id, title, text)| FeedContextEntry {
+                id: format!("urn:uuid:{}", id),
+                title,
+                text,
+                updated: FeedContextTime::from(time),
+            })
+            .collect(),
+
+        config: &INDEX_CONFIG,
+    }
+}
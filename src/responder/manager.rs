@@ -46,9 +46,10 @@ pub fn run() {
     let tera = Tera::new(&templates).unwrap();
 
     // Prepare authentication middlewares
-    let (middleware_reporter_auth, middleware_manager_auth) = (
+    let (middleware_reporter_auth, middleware_manager_auth, middleware_viewer_auth) = (
         HttpAuthentication::basic(authenticate_reporter),
         HttpAuthentication::basic(authenticate_manager),
+        HttpAuthentication::basic(authenticate_viewer),
     );
 
     // Prepare MCP services (if enabled)
@@ -82,11 +83,23 @@ pub fn run() {
             .service(routes::assets_images)
             .service(routes::assets_fonts)
             .service(routes::badge)
+            .service(routes::badge_probe)
+            .service(routes::badge_node)
+            .service(routes::feed_atom)
+            .service(routes::feed_rss)
             .service(routes::status_text)
+            .service(routes::status_stream)
             .service(routes::status_report)
+            .service(routes::api_status)
             .service(routes::robots)
             .service(routes::index)
             .app_data(ConfigAuth::default().realm("Reporter Token"))
+            .service(
+                web::resource("/private")
+                    .wrap(middleware_viewer_auth.clone())
+                    .guard(guard::Get())
+                    .to(routes::private_index),
+            )
             .service(
                 web::resource("/reporter/{probe_id}/{node_id}")
                     .wrap(middleware_reporter_auth.clone())
@@ -117,6 +130,54 @@ pub fn run() {
                     .guard(guard::Delete())
                     .to(routes::manager_announcement_retract),
             )
+            .service(
+                web::resource("/manager/notifications")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Get())
+                    .to(routes::manager_notifications),
+            )
+            .service(
+                web::resource("/manager/notify/test")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Post())
+                    .to(routes::manager_notify_test),
+            )
+            .service(
+                web::resource("/manager/status/report")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Get())
+                    .to(routes::manager_status_report),
+            )
+            .service(
+                web::resource("/manager/probes")
+                    .wrap(middleware_manager_auth.clone())
+                    .route(web::get().to(routes::manager_probes))
+                    .route(web::post().to(routes::manager_probe_upsert)),
+            )
+            .service(
+                web::resource("/manager/probes/{probe_id}")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Delete())
+                    .to(routes::manager_probe_remove),
+            )
+            .service(
+                web::resource("/manager/probes/{probe_id}/nodes")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Post())
+                    .to(routes::manager_probe_node_upsert),
+            )
+            .service(
+                web::resource("/manager/probes/{probe_id}/nodes/{node_id}")
+                    .wrap(middleware_manager_auth.clone())
+                    .guard(guard::Delete())
+                    .to(routes::manager_probe_node_remove),
+            )
+            .service(
+                web::resource("/manager/probes/{probe_id}/nodes/{node_id}/replicas")
+                    .wrap(middleware_manager_auth.clone())
+                    .route(web::post().to(routes::manager_probe_replica_insert))
+                    .route(web::delete().to(routes::manager_probe_replica_remove)),
+            )
             .service(
                 web::resource("/manager/prober/alerts")
                     .wrap(middleware_manager_auth.clone())
@@ -136,6 +197,26 @@ pub fn run() {
                     .to(routes::manager_prober_alerts_ignored_update),
             );
 
+        // Add subscribe services?
+        if APP_CONF.subscribers.is_some() {
+            app = app
+                .service(
+                    web::resource("/subscribe")
+                        .route(web::get().to(routes::subscribe_form))
+                        .route(web::post().to(routes::subscribe_submit)),
+                )
+                .service(
+                    web::resource("/subscribe/confirm/{token}")
+                        .route(web::get().to(routes::subscribe_confirm_prompt))
+                        .route(web::post().to(routes::subscribe_confirm)),
+                )
+                .service(
+                    web::resource("/subscribe/unsubscribe/{token}")
+                        .route(web::get().to(routes::subscribe_unsubscribe_prompt))
+                        .route(web::post().to(routes::subscribe_unsubscribe)),
+                );
+        }
+
         // Add MCP services?
         if let Some(mcp_services) = mcp_services.clone() {
             app = app.service(
@@ -193,3 +274,18 @@ async fn authenticate_manager(
 ) -> Result<ServiceRequest, (ActixError, ServiceRequest)> {
     authenticate(request, credentials, &APP_CONF.server.manager_token)
 }
+
+async fn authenticate_viewer(
+    request: ServiceRequest,
+    credentials: BasicAuth,
+) -> Result<ServiceRequest, (ActixError, ServiceRequest)> {
+    // Notice: the manager token also grants access to private views, while the viewer token \
+    //   (if any) only grants access to private views.
+    if let Some(ref viewer_token) = APP_CONF.server.viewer_token {
+        if credentials.password() == Some(viewer_token.as_str()) {
+            return Ok(request);
+        }
+    }
+
+    authenticate(request, credentials, &APP_CONF.server.manager_token)
+}
@@ -14,6 +14,7 @@ use rmcp::{
 };
 
 use super::payload::StatusReportResponsePayload;
+use crate::prober::visibility::Visibility;
 
 #[derive(serde::Deserialize, schemars::JsonSchema)]
 pub struct ProbesRequest {}
@@ -32,13 +33,13 @@ impl Probes {
     }
 
     #[tool(
-        description = "Returns all Vigil Status Page services and nodes and reports their status (healthy, sick, dead)"
+        description = "Returns all public Vigil Status Page services and nodes and reports their status (healthy, sick, dead)"
     )]
     fn get_report(
         &self,
         Parameters(_): Parameters<ProbesRequest>,
     ) -> Json<StatusReportResponsePayload> {
-        Json(StatusReportResponsePayload::build())
+        Json(StatusReportResponsePayload::build(Visibility::Public))
     }
 }
 
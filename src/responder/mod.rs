@@ -5,9 +5,12 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 mod announcements;
+mod badge;
 mod context;
+mod feed;
 mod mcp;
 mod payload;
 mod routes;
 
+pub mod events;
 pub mod manager;
@@ -4,10 +4,18 @@
 // Copyright: 2021, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use rmcp::schemars;
 
+use crate::aggregator::history::STORE as HISTORY_STORE;
 use crate::prober::manager::STORE as PROBER_STORE;
+use crate::prober::mode::Mode;
+use crate::prober::states::{
+    ServiceStatesProbeNodeReplicaLoad, ServiceStatesProbeNodeReplicaMetrics,
+};
 use crate::prober::status::Status as HealthStatus;
+use crate::prober::visibility::Visibility;
 use crate::APP_CONF;
 
 #[derive(Deserialize)]
@@ -35,6 +43,50 @@ pub struct ManagerProberAlertsIgnoredResolveRequestPayload {
     pub reminders_seconds: Option<u16>,
 }
 
+#[derive(Deserialize)]
+pub struct ManagerNotificationsRequestQuery {
+    pub notifier: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ManagerNotifyTestRequestPayload {
+    pub notifier: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ManagerProbeReplicaInsertRequestPayload {
+    pub replica: Option<String>,
+    pub script: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ManagerProbeReplicaRemoveRequestQuery {
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+pub struct StatusApiRequestQuery {
+    pub probe: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BadgeRequestQuery {
+    pub label: Option<String>,
+    pub uptime: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct BadgeShieldsResponsePayload {
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: u8,
+
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
 #[derive(Serialize)]
 pub struct ManagerAnnouncementsResponsePayload {
     pub id: String,
@@ -46,6 +98,30 @@ pub struct ManagerAnnouncementInsertResponsePayload {
     pub id: String,
 }
 
+#[derive(Serialize)]
+pub struct ManagerProbesResponsePayload {
+    pub id: String,
+    pub label: String,
+    pub status: HealthStatus,
+    pub visibility: Visibility,
+    pub nodes: Vec<ManagerProbesResponsePayloadNode>,
+}
+
+#[derive(Serialize)]
+pub struct ManagerProbesResponsePayloadNode {
+    pub id: String,
+    pub label: String,
+    pub mode: Mode,
+    pub status: HealthStatus,
+    pub visibility: Visibility,
+    pub replicas: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ManagerProbeReplicaInsertResponsePayload {
+    pub id: String,
+}
+
 #[derive(Serialize, Default)]
 pub struct ManagerProberAlertsResponsePayload {
     pub dead: Vec<ManagerProberAlertsResponsePayloadEntry>,
@@ -92,11 +168,12 @@ pub struct StatusReportResponsePayloadProbeNode {
 }
 
 impl StatusReportResponsePayload {
-    pub fn build() -> Self {
-        let states = &PROBER_STORE.read().unwrap().states;
+    pub fn build(scope: Visibility) -> Self {
+        let store = PROBER_STORE.read().unwrap();
+        let states = store.states.view(scope, false);
 
         StatusReportResponsePayload {
-            health: states.status.clone(),
+            health: states.status,
             page: StatusReportResponsePayloadPage {
                 name: APP_CONF.branding.page_title.to_owned(),
                 url: APP_CONF.branding.page_url.to_string(),
@@ -105,14 +182,14 @@ impl StatusReportResponsePayload {
                 .probes
                 .iter()
                 .map(|(_, probe)| StatusReportResponsePayloadProbe {
-                    name: probe.label.clone(),
-                    status: probe.status.clone(),
+                    name: probe.label.to_owned(),
+                    status: probe.status,
                     nodes: probe
                         .nodes
                         .iter()
                         .map(|(_, node)| StatusReportResponsePayloadProbeNode {
-                            name: node.label.clone(),
-                            status: node.status.clone(),
+                            name: node.label.to_owned(),
+                            status: node.status,
                             replicas: node
                                 .replicas
                                 .iter()
@@ -125,3 +202,173 @@ impl StatusReportResponsePayload {
         }
     }
 }
+
+#[derive(Serialize)]
+pub struct StatusApiResponsePayload {
+    status: HealthStatus,
+    date: Option<String>,
+    page: StatusReportResponsePayloadPage,
+    probes: Vec<StatusApiResponsePayloadProbe>,
+}
+
+#[derive(Serialize)]
+pub struct StatusApiResponsePayloadProbe {
+    pub id: String,
+    pub label: String,
+    pub status: HealthStatus,
+    pub uptime: Option<f64>,
+    pub changed: Option<u64>,
+    pub depends_on: Vec<String>,
+    pub impacted_by: Vec<String>,
+    pub nodes: Vec<StatusApiResponsePayloadProbeNode>,
+    pub history: Vec<StatusApiResponsePayloadProbeTransition>,
+}
+
+#[derive(Serialize)]
+pub struct StatusApiResponsePayloadProbeNode {
+    pub id: String,
+    pub label: String,
+    pub mode: Mode,
+    pub status: HealthStatus,
+    pub uptime: Option<f64>,
+    pub changed: Option<u64>,
+    pub impacted_by: Vec<String>,
+    pub replicas: Vec<StatusApiResponsePayloadProbeNodeReplica>,
+}
+
+#[derive(Serialize)]
+pub struct StatusApiResponsePayloadProbeNodeReplica {
+    pub index: usize,
+    pub id: Option<String>,
+    pub status: HealthStatus,
+    pub changed: Option<u64>,
+    pub checked: Option<u64>,
+    pub metrics: ServiceStatesProbeNodeReplicaMetrics,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load: Option<ServiceStatesProbeNodeReplicaLoad>,
+}
+
+#[derive(Serialize)]
+pub struct StatusApiResponsePayloadProbeTransition {
+    pub from: HealthStatus,
+    pub to: HealthStatus,
+    pub time: u64,
+}
+
+impl StatusApiResponsePayload {
+    pub fn build(probe_ids: Option<Vec<&str>>) -> Option<Self> {
+        let store = PROBER_STORE.read().unwrap();
+        // Notice: a detailed view is used there, for replica loads to be reported (other \
+        //   replica details are not reported, as they may reveal sensitive information).
+        let states = store.states.view(Visibility::Public, true);
+
+        // Filter on requested services? (unknown services cannot be reported)
+        if let Some(ref probe_ids) = probe_ids {
+            if probe_ids
+                .iter()
+                .any(|probe_id| !states.probes.contains_key(probe_id))
+            {
+                return None;
+            }
+        }
+
+        let history_store = HISTORY_STORE.read().unwrap();
+
+        Some(StatusApiResponsePayload {
+            status: states.status,
+            date: states.date.to_owned(),
+            page: StatusReportResponsePayloadPage {
+                name: APP_CONF.branding.page_title.to_owned(),
+                url: APP_CONF.branding.page_url.to_string(),
+            },
+            probes: states
+                .probes
+                .iter()
+                .filter(|(probe_id, _)| {
+                    probe_ids
+                        .as_ref()
+                        .map(|probe_ids| probe_ids.contains(probe_id))
+                        .unwrap_or(true)
+                })
+                .map(|(probe_id, probe)| StatusApiResponsePayloadProbe {
+                    id: probe_id.to_string(),
+                    label: probe.label.to_owned(),
+                    status: probe.status,
+                    uptime: probe.uptime.percent(),
+                    changed: probe.changed.map(unix_time),
+                    depends_on: probe
+                        .depends_on
+                        .iter()
+                        .map(|dependency| dependency.id.to_owned())
+                        .collect(),
+                    impacted_by: probe
+                        .impacted_by
+                        .iter()
+                        .map(|dependency| dependency.id.to_owned())
+                        .collect(),
+                    nodes: probe
+                        .nodes
+                        .iter()
+                        .map(|(node_id, node)| StatusApiResponsePayloadProbeNode {
+                            id: node_id.to_string(),
+                            label: node.label.to_owned(),
+                            mode: node.mode.to_owned(),
+                            status: node.status,
+                            uptime: node.uptime.percent(),
+                            changed: node.changed.map(unix_time),
+                            impacted_by: node
+                                .impacted_by
+                                .iter()
+                                .map(|dependency| dependency.id.to_owned())
+                                .collect(),
+                            replicas: node
+                                .replicas
+                                .iter()
+                                .enumerate()
+                                .map(|(replica_index, (replica_id, replica))| {
+                                    StatusApiResponsePayloadProbeNodeReplica {
+                                        index: replica_index + 1,
+
+                                        // Replica names are not revealed, unless the node is \
+                                        //   configured to do so (they may hold private URLs)
+                                        id: if node.reveal_replica_name {
+                                            Some(replica_id.to_string())
+                                        } else {
+                                            None
+                                        },
+
+                                        status: replica.status,
+                                        changed: replica.changed.map(unix_time),
+                                        checked: replica.checked.map(unix_time),
+                                        metrics: replica.metrics.to_owned(),
+                                        load: replica
+                                            .details
+                                            .as_ref()
+                                            .and_then(|details| details.load.to_owned()),
+                                    }
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    history: history_store
+                        .transitions
+                        .iter()
+                        .filter(|transition| transition.probe_id == *probe_id)
+                        .map(|transition| StatusApiResponsePayloadProbeTransition {
+                            from: transition.from,
+                            to: transition.to,
+                            time: unix_time(transition.time),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
@@ -5,46 +5,102 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 use actix_files::NamedFile;
-use actix_web::{get, web, web::Data, web::Json, HttpResponse, Responder, Result};
+use actix_web::{
+    error::BlockingError, get, http::header, web, web::Data, web::Json, HttpRequest, HttpResponse,
+    Responder, Result,
+};
 use std::time::{Duration, SystemTime};
 use tera::Tera;
 use time;
+use url::form_urlencoded;
 use uuid::Uuid;
 
 use super::announcements::{
     Announcement, DATE_NOW_FORMATTER as ANNOUNCEMENTS_DATE_NOW_FORMATTER,
     STORE as ANNOUNCEMENTS_STORE,
 };
-use super::context::{IndexContext, INDEX_CONFIG, INDEX_ENVIRONMENT};
+use super::badge::Badge;
+use super::context::{
+    IndexContext, SubscribeContext, SubscribeContextChannels, SubscribeContextService,
+    SubscribeOutcome, INDEX_CONFIG, INDEX_ENVIRONMENT,
+};
+use super::events::{
+    publish_announcement_insert as publish_announcement_insert_event,
+    publish_announcement_retract as publish_announcement_retract_event,
+    subscribe as subscribe_events,
+};
+use super::feed::{make_context as make_feed_context, FeedFormat};
 use super::payload::{
-    ManagerAnnouncementInsertRequestPayload, ManagerAnnouncementInsertResponsePayload,
-    ManagerAnnouncementsResponsePayload, ManagerProberAlertsIgnoredResolveRequestPayload,
+    BadgeRequestQuery, BadgeShieldsResponsePayload, ManagerAnnouncementInsertRequestPayload,
+    ManagerAnnouncementInsertResponsePayload, ManagerAnnouncementsResponsePayload,
+    ManagerNotificationsRequestQuery, ManagerNotifyTestRequestPayload,
+    ManagerProbeReplicaInsertRequestPayload, ManagerProbeReplicaInsertResponsePayload,
+    ManagerProbeReplicaRemoveRequestQuery, ManagerProberAlertsIgnoredResolveRequestPayload,
     ManagerProberAlertsIgnoredResolveResponsePayload, ManagerProberAlertsResponsePayload,
-    ManagerProberAlertsResponsePayloadEntry, ReporterRequestPayload, StatusReportResponsePayload,
+    ManagerProberAlertsResponsePayloadEntry, ManagerProbesResponsePayload,
+    ManagerProbesResponsePayloadNode, ReporterRequestPayload, StatusApiRequestQuery,
+    StatusApiResponsePayload, StatusReportResponsePayload,
+};
+use crate::config::config::{ConfigProbeService, ConfigProbeServiceNode};
+use crate::notifier::dispatcher::{
+    dispatch_test as dispatch_test_notification, DispatchLogEntry,
+    STORE as NOTIFIER_DISPATCHER_STORE,
 };
 use crate::prober::manager::{run_dispatch_plugins, STORE as PROBER_STORE};
+use crate::prober::registry::{
+    insert_replica as insert_registry_replica, remove_node as remove_registry_node,
+    remove_replica as remove_registry_replica, remove_service as remove_registry_service,
+    upsert_node as upsert_registry_node, upsert_service as upsert_registry_service, RegistryError,
+};
 use crate::prober::report::{
     handle_flush as handle_flush_report, handle_health as handle_health_report,
     handle_load as handle_load_report, HandleFlushError, HandleHealthError, HandleLoadError,
 };
 use crate::prober::status::Status;
+use crate::prober::visibility::Visibility;
+use crate::subscriber::dispatcher::{
+    dispatch_announcement as dispatch_subscriber_announcement,
+    dispatch_confirm as dispatch_subscriber_confirm,
+};
+use crate::subscriber::store::{
+    can_confirm as can_confirm_subscriber, can_unsubscribe as can_unsubscribe_subscriber,
+    confirm as confirm_subscriber, subscribe as subscribe_subscriber,
+    unsubscribe as unsubscribe_subscriber, SubscribeError, SubscriberChannel,
+};
 use crate::APP_CONF;
 
+const BADGE_CACHE_CONTROL: &str = "no-cache, max-age=0";
+
 #[get("/")]
 async fn index(tera: Data<Tera>) -> HttpResponse {
+    render_index(&tera, false)
+}
+
+fn render_index(tera: &Tera, private: bool) -> HttpResponse {
     // Notice acquire lock in a block to release it ASAP (ie. before template renders)
     let context = {
-        IndexContext {
-            states: &PROBER_STORE.read().unwrap().states,
-            announcements: &ANNOUNCEMENTS_STORE.read().unwrap().announcements,
+        let (prober_store, announcements_store) = (
+            PROBER_STORE.read().unwrap(),
+            ANNOUNCEMENTS_STORE.read().unwrap(),
+        );
+
+        // Private views include internal services and all replica details
+        let states = if private {
+            prober_store.states.view(Visibility::Internal, true)
+        } else {
+            prober_store.states.view(Visibility::Public, false)
+        };
+
+        tera::Context::from_serialize(IndexContext {
+            states,
+            announcements: &announcements_store.announcements,
+            private,
             environment: &*INDEX_ENVIRONMENT,
             config: &*INDEX_CONFIG,
-        }
+        })
+        .unwrap()
     };
-    let render = tera.render(
-        "index.tera",
-        &tera::Context::from_serialize(context).unwrap(),
-    );
+    let render = tera.render("index.tera", &context);
     if let Ok(s) = render {
         HttpResponse::Ok().content_type("text/html").body(s)
     } else {
@@ -52,6 +108,28 @@ async fn index(tera: Data<Tera>) -> HttpResponse {
     }
 }
 
+#[get("/feed.atom")]
+async fn feed_atom(tera: Data<Tera>) -> HttpResponse {
+    render_feed(&tera, FeedFormat::Atom)
+}
+
+#[get("/feed.rss")]
+async fn feed_rss(tera: Data<Tera>) -> HttpResponse {
+    render_feed(&tera, FeedFormat::Rss)
+}
+
+fn render_feed(tera: &Tera, format: FeedFormat) -> HttpResponse {
+    let context = tera::Context::from_serialize(make_feed_context(&format)).unwrap();
+    let render = tera.render(format.template(), &context);
+    if let Ok(s) = render {
+        HttpResponse::Ok()
+            .content_type(format.content_type())
+            .body(s)
+    } else {
+        HttpResponse::InternalServerError().body(format!("Template Error {:?}", render))
+    }
+}
+
 #[get("/robots.txt")]
 async fn robots() -> Option<NamedFile> {
     NamedFile::open(APP_CONF.assets.path.join("public").join("robots.txt")).ok()
@@ -59,38 +137,156 @@ async fn robots() -> Option<NamedFile> {
 
 #[get("/status/text")]
 async fn status_text() -> &'static str {
-    &PROBER_STORE.read().unwrap().states.status.as_str()
+    PROBER_STORE
+        .read()
+        .unwrap()
+        .states
+        .view(Visibility::Public, false)
+        .status
+        .as_str()
+}
+
+#[get("/status/stream")]
+async fn status_stream() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(subscribe_events())
 }
 
 #[get("/status/report")]
 async fn status_report() -> Result<impl Responder> {
-    Ok(web::Json(StatusReportResponsePayload::build()))
+    Ok(web::Json(StatusReportResponsePayload::build(
+        Visibility::Public,
+    )))
+}
+
+#[get("/api/v1/status")]
+async fn api_status(query: web::Query<StatusApiRequestQuery>) -> HttpResponse {
+    // Notice: services can be filtered by passing a comma-separated list of their identifiers
+    let probe_ids = query
+        .probe
+        .as_ref()
+        .map(|probe| probe.split(',').map(|probe_id| probe_id.trim()).collect());
+
+    match StatusApiResponsePayload::build(probe_ids) {
+        Some(payload) => HttpResponse::Ok().json(payload),
+        None => HttpResponse::NotFound().finish(),
+    }
 }
 
 #[get("/badge/{kind}")]
-async fn badge(kind: web::Path<String>) -> Option<NamedFile> {
-    // Notice acquire lock in a block to release it ASAP (ie. before OS access to file)
-    let status = { &PROBER_STORE.read().unwrap().states.status.as_str() };
+async fn badge(
+    request: HttpRequest,
+    kind: web::Path<String>,
+    query: web::Query<BadgeRequestQuery>,
+) -> HttpResponse {
+    respond_badge(&request, &kind.into_inner(), None, None, &query)
+}
 
-    if let Ok(badge_file) = NamedFile::open(
-        APP_CONF
-            .assets
-            .path
-            .join("images")
-            .join("badges")
-            .join(format!("{}-{}-default.svg", kind, status)),
-    ) {
-        // Return badge file without 'Last-Modified' HTTP header, which would otherwise hold the \
-        //   date the actual badge image file was last modified, which is not what we want there, \
-        //   as it would make browsers believe they can use a previous cache they hold, on a \
-        //   badge image that can be for a different status.
-        Some(
-            badge_file
-                .disable_content_disposition()
-                .use_last_modified(false),
+#[get("/badge/{kind}/{probe_id}")]
+async fn badge_probe(
+    request: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<BadgeRequestQuery>,
+) -> HttpResponse {
+    // Read path information
+    let info = path.into_inner();
+
+    let (kind, probe_id) = (info.0, info.1);
+
+    respond_badge(&request, &kind, Some(&probe_id), None, &query)
+}
+
+#[get("/badge/{kind}/{probe_id}/{node_id}")]
+async fn badge_node(
+    request: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<BadgeRequestQuery>,
+) -> HttpResponse {
+    // Read path information
+    let info = path.into_inner();
+
+    let (kind, probe_id, node_id) = (info.0, info.1, info.2);
+
+    respond_badge(&request, &kind, Some(&probe_id), Some(&node_id), &query)
+}
+
+fn respond_badge(
+    request: &HttpRequest,
+    kind: &str,
+    probe_id: Option<&str>,
+    node_id: Option<&str>,
+    query: &BadgeRequestQuery,
+) -> HttpResponse {
+    // Notice acquire lock in a block to release it ASAP (ie. before OS access to file)
+    let status_badge = {
+        Badge::resolve(
+            &PROBER_STORE
+                .read()
+                .unwrap()
+                .states
+                .view(Visibility::Public, false),
+            probe_id,
+            node_id,
         )
+    };
+
+    // Notice: non-visible services and nodes are reported as not found, as if they did not \
+    //   exist (so that their existence does not leak through badges).
+    let mut status_badge = if let Some(status_badge) = status_badge {
+        status_badge
     } else {
-        None
+        return HttpResponse::NotFound().finish();
+    };
+
+    // Apply badge overrides (if any)
+    if let Some(ref label) = query.label {
+        status_badge.relabel(label);
+    }
+    if query.uptime == Some(false) {
+        status_badge.uptime = None;
+    }
+
+    match kind {
+        "flat" => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, BADGE_CACHE_CONTROL))
+            .content_type("image/svg+xml")
+            .body(status_badge.render_svg()),
+        "shields" => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, BADGE_CACHE_CONTROL))
+            .json(BadgeShieldsResponsePayload {
+                schema_version: 1,
+                label: status_badge.label.to_owned(),
+                message: status_badge.message(),
+                color: status_badge.color(),
+            }),
+        _ => {
+            if let Ok(badge_file) = NamedFile::open(
+                APP_CONF
+                    .assets
+                    .path
+                    .join("images")
+                    .join("badges")
+                    .join(format!(
+                        "{}-{}-default.svg",
+                        kind,
+                        status_badge.status.as_str()
+                    )),
+            ) {
+                // Return badge file without 'Last-Modified' HTTP header, which would otherwise \
+                //   hold the date the actual badge image file was last modified, which is not \
+                //   what we want there, as it would make browsers believe they can use a \
+                //   previous cache they hold, on a badge image that can be for a different status.
+                badge_file
+                    .disable_content_disposition()
+                    .use_last_modified(false)
+                    .into_response(request)
+            } else {
+                HttpResponse::NotFound().finish()
+            }
+        }
     }
 }
 
@@ -132,6 +328,176 @@ async fn assets_javascripts(file: web::Path<String>) -> Option<NamedFile> {
     NamedFile::open(APP_CONF.assets.path.join("javascripts").join(file)).ok()
 }
 
+// Notice: private index route is managed in manager due to authentication needs
+pub async fn private_index(tera: Data<Tera>) -> HttpResponse {
+    render_index(&tera, true)
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_form(tera: Data<Tera>) -> HttpResponse {
+    render_subscribe(&tera, None, None)
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_submit(tera: Data<Tera>, body: web::Bytes) -> HttpResponse {
+    let (mut kind, mut target, mut services) = (String::new(), String::new(), Vec::new());
+
+    // Notice: parse form manually, as it holds a list of services (ie. repeated fields)
+    for (key, value) in form_urlencoded::parse(&body) {
+        match key.as_ref() {
+            "channel" => kind = value.into_owned(),
+            "target" => target = value.trim().to_string(),
+            "services" => services.push(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    // Only public services can be subscribed to
+    let has_valid_services = {
+        let prober_store = PROBER_STORE.read().unwrap();
+        let states = prober_store.states.view(Visibility::Public, false);
+
+        services
+            .iter()
+            .all(|service| states.probes.contains_key(service.as_str()))
+    };
+
+    let outcome = match SubscriberChannel::from_form(&kind, &target) {
+        Some(channel) if has_valid_services => {
+            // Subscribe in a blocking thread (the subscribers store gets saved to disk)
+            match web::block(move || subscribe_subscriber(channel, services)).await {
+                Ok(Ok(subscriber)) => {
+                    dispatch_subscriber_confirm(&subscriber);
+
+                    SubscribeOutcome::Pending
+                }
+                Ok(Err(SubscribeError::Full)) => SubscribeOutcome::Full,
+                Ok(Err(SubscribeError::Disabled)) | Err(_) => SubscribeOutcome::Invalid,
+            }
+        }
+        _ => SubscribeOutcome::Invalid,
+    };
+
+    render_subscribe(&tera, Some(outcome), None)
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_confirm_prompt(tera: Data<Tera>, token: web::Path<String>) -> HttpResponse {
+    // Notice: opening a confirmation link does not confirm the subscription, as links may be \
+    //   opened by mail scanners. A form gets shown instead, which confirms upon submission.
+    if can_confirm_subscriber(&token) {
+        render_subscribe(&tera, Some(SubscribeOutcome::ConfirmPrompt), Some(&token))
+    } else {
+        render_subscribe(&tera, Some(SubscribeOutcome::Unknown), None)
+    }
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_confirm(tera: Data<Tera>, token: web::Path<String>) -> HttpResponse {
+    // Confirm in a blocking thread (the subscribers store gets saved to disk)
+    let confirmed = web::block(move || confirm_subscriber(&token.into_inner())).await;
+
+    let outcome = if let Ok(Some(_)) = confirmed {
+        SubscribeOutcome::Confirmed
+    } else {
+        SubscribeOutcome::Unknown
+    };
+
+    render_subscribe(&tera, Some(outcome), None)
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_unsubscribe_prompt(
+    tera: Data<Tera>,
+    token: web::Path<String>,
+) -> HttpResponse {
+    // Notice: opening an unsubscribe link does not unsubscribe, as links may be opened by mail \
+    //   scanners. A form gets shown instead, which unsubscribes upon submission (this route \
+    //   also serves one-click unsubscribe requests from mail clients, which are POST requests).
+    if can_unsubscribe_subscriber(&token) {
+        render_subscribe(
+            &tera,
+            Some(SubscribeOutcome::UnsubscribePrompt),
+            Some(&token),
+        )
+    } else {
+        render_subscribe(&tera, Some(SubscribeOutcome::Unknown), None)
+    }
+}
+
+// Notice: subscribe routes are managed in manager, as they are only mounted if subscribers are \
+//   enabled
+pub async fn subscribe_unsubscribe(tera: Data<Tera>, token: web::Path<String>) -> HttpResponse {
+    // Unsubscribe in a blocking thread (the subscribers store gets saved to disk)
+    let unsubscribed = web::block(move || unsubscribe_subscriber(&token.into_inner())).await;
+
+    let outcome = if let Ok(Some(_)) = unsubscribed {
+        SubscribeOutcome::Unsubscribed
+    } else {
+        SubscribeOutcome::Unknown
+    };
+
+    render_subscribe(&tera, Some(outcome), None)
+}
+
+fn render_subscribe(
+    tera: &Tera,
+    outcome: Option<SubscribeOutcome>,
+    token: Option<&str>,
+) -> HttpResponse {
+    let mut response = match outcome {
+        Some(SubscribeOutcome::Invalid) => HttpResponse::BadRequest(),
+        Some(SubscribeOutcome::Unknown) => HttpResponse::NotFound(),
+        Some(SubscribeOutcome::Full) => HttpResponse::ServiceUnavailable(),
+        _ => HttpResponse::Ok(),
+    };
+
+    // Notice acquire lock in a block to release it ASAP (ie. before template renders)
+    let context = {
+        let prober_store = PROBER_STORE.read().unwrap();
+        let states = prober_store.states.view(Visibility::Public, false);
+
+        tera::Context::from_serialize(SubscribeContext {
+            services: states
+                .probes
+                .values()
+                .map(|probe| SubscribeContextService {
+                    id: probe.id,
+                    label: probe.label,
+                })
+                .collect(),
+            channels: SubscribeContextChannels {
+                email: APP_CONF
+                    .subscribers
+                    .as_ref()
+                    .map(|subscribers| subscribers.email)
+                    .unwrap_or(false),
+                webhook: APP_CONF
+                    .subscribers
+                    .as_ref()
+                    .map(|subscribers| subscribers.webhook)
+                    .unwrap_or(false),
+            },
+            outcome,
+            token,
+            environment: &INDEX_ENVIRONMENT,
+            config: &INDEX_CONFIG,
+        })
+        .unwrap()
+    };
+    let render = tera.render("subscribe.tera", &context);
+    if let Ok(s) = render {
+        response.content_type("text/html").body(s)
+    } else {
+        HttpResponse::InternalServerError().body(format!("Template Error {:?}", render))
+    }
+}
+
 // Notice: reporter report route is managed in manager due to authentication needs
 pub async fn reporter_report(
     path: web::Path<(String, String)>,
@@ -223,7 +589,7 @@ pub async fn manager_announcement_insert(
 
         let mut store = ANNOUNCEMENTS_STORE.write().unwrap();
 
-        store.announcements.push(Announcement {
+        let announcement = Announcement {
             id: id.to_owned(),
             title: data.title.to_owned(),
             text: data.text.to_owned(),
@@ -233,7 +599,13 @@ pub async fn manager_announcement_insert(
                     .format(&ANNOUNCEMENTS_DATE_NOW_FORMATTER)
                     .unwrap_or("?".to_string()),
             ),
-        });
+            time: SystemTime::now(),
+        };
+
+        publish_announcement_insert_event(&announcement);
+        dispatch_subscriber_announcement(&announcement.title, &announcement.text);
+
+        store.announcements.push(announcement);
 
         HttpResponse::Ok().json(ManagerAnnouncementInsertResponsePayload { id: id })
     } else {
@@ -257,12 +629,60 @@ pub async fn manager_announcement_retract(announcement_id: web::Path<String>) ->
         // Remove target announcement
         store.announcements.remove(announcement_index);
 
+        publish_announcement_retract_event(&announcement_id);
+
         HttpResponse::Ok().finish()
     } else {
         HttpResponse::NotFound().finish()
     }
 }
 
+// Notice: manager notifications route is managed in manager due to authentication needs
+pub async fn manager_notifications(
+    query: web::Query<ManagerNotificationsRequestQuery>,
+) -> HttpResponse {
+    // List all logged notification attempts matching filters (most recent first)
+    HttpResponse::Ok().json(
+        NOTIFIER_DISPATCHER_STORE
+            .read()
+            .unwrap()
+            .log
+            .iter()
+            .rev()
+            .filter(|entry| {
+                query
+                    .notifier
+                    .as_ref()
+                    .map(|notifier| notifier == entry.notifier)
+                    .unwrap_or(true)
+                    && query.since.map(|since| entry.time >= since).unwrap_or(true)
+                    && query.until.map(|until| entry.time <= until).unwrap_or(true)
+            })
+            .cloned()
+            .collect::<Vec<DispatchLogEntry>>(),
+    )
+}
+
+// Notice: manager notify test route is managed in manager due to authentication needs
+pub async fn manager_notify_test(data: Json<ManagerNotifyTestRequestPayload>) -> HttpResponse {
+    let notifier = data.into_inner().notifier;
+
+    // Dispatch test notification in a blocking thread (notifiers are synchronous)
+    let results = web::block(move || dispatch_test_notification(notifier.as_deref())).await;
+
+    match results {
+        Ok(Some(results)) => HttpResponse::Ok().json(results),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+// Notice: manager status report route is managed in manager due to authentication needs
+pub async fn manager_status_report() -> HttpResponse {
+    // Report on public and internal services (hidden services are never reported)
+    HttpResponse::Ok().json(StatusReportResponsePayload::build(Visibility::Internal))
+}
+
 // Notice: manager prober alerts route is managed in manager due to authentication needs
 pub async fn manager_prober_alerts() -> HttpResponse {
     let mut alerts = ManagerProberAlertsResponsePayload::default();
@@ -327,3 +747,139 @@ pub async fn manager_prober_alerts_ignored_update(
 
     HttpResponse::Ok().finish()
 }
+
+// Notice: manager probes route is managed in manager due to authentication needs
+pub async fn manager_probes() -> HttpResponse {
+    // List all services, nodes and replicas in store (including hidden ones)
+    HttpResponse::Ok().json(
+        PROBER_STORE
+            .read()
+            .unwrap()
+            .states
+            .probes
+            .iter()
+            .map(|(probe_id, probe)| ManagerProbesResponsePayload {
+                id: probe_id.to_owned(),
+                label: probe.label.to_owned(),
+                status: probe.status,
+                visibility: probe.visibility,
+                nodes: probe
+                    .nodes
+                    .iter()
+                    .map(|(node_id, node)| ManagerProbesResponsePayloadNode {
+                        id: node_id.to_owned(),
+                        label: node.label.to_owned(),
+                        mode: node.mode.to_owned(),
+                        status: node.status,
+                        visibility: node.visibility,
+                        replicas: node.replicas.keys().cloned().collect(),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<ManagerProbesResponsePayload>>(),
+    )
+}
+
+// Notice: manager probe upsert route is managed in manager due to authentication needs
+pub async fn manager_probe_upsert(data: Json<ConfigProbeService>) -> HttpResponse {
+    // Upsert in a blocking thread (HTTP clients get built, and TLS files get read)
+    let result = web::block(move || upsert_registry_service(&data)).await;
+
+    respond_registry_blocking(result, respond_registry_upsert)
+}
+
+// Notice: manager probe remove route is managed in manager due to authentication needs
+pub async fn manager_probe_remove(path: web::Path<String>) -> HttpResponse {
+    // Remove in a blocking thread (dropping HTTP clients waits for their threads to stop)
+    let result = web::block(move || remove_registry_service(&path.into_inner())).await;
+
+    respond_registry_blocking(result, respond_registry_remove)
+}
+
+// Notice: manager probe node upsert route is managed in manager due to authentication needs
+pub async fn manager_probe_node_upsert(
+    path: web::Path<String>,
+    data: Json<ConfigProbeServiceNode>,
+) -> HttpResponse {
+    // Upsert in a blocking thread (HTTP clients get built, and TLS files get read)
+    let result = web::block(move || upsert_registry_node(&path.into_inner(), &data)).await;
+
+    respond_registry_blocking(result, respond_registry_upsert)
+}
+
+// Notice: manager probe node remove route is managed in manager due to authentication needs
+pub async fn manager_probe_node_remove(path: web::Path<(String, String)>) -> HttpResponse {
+    let (probe_id, node_id) = path.into_inner();
+
+    // Remove in a blocking thread (dropping HTTP clients waits for their threads to stop)
+    let result = web::block(move || remove_registry_node(&probe_id, &node_id)).await;
+
+    respond_registry_blocking(result, respond_registry_remove)
+}
+
+// Notice: manager probe replica insert route is managed in manager due to authentication needs
+pub async fn manager_probe_replica_insert(
+    path: web::Path<(String, String)>,
+    data: Json<ManagerProbeReplicaInsertRequestPayload>,
+) -> HttpResponse {
+    let (probe_id, node_id) = path.into_inner();
+
+    match insert_registry_replica(
+        &probe_id,
+        &node_id,
+        data.replica.as_deref(),
+        data.script.as_deref(),
+    ) {
+        Ok(replica_id) => {
+            HttpResponse::Ok().json(ManagerProbeReplicaInsertResponsePayload { id: replica_id })
+        }
+        Err(err) => respond_registry_error(err),
+    }
+}
+
+// Notice: manager probe replica remove route is managed in manager due to authentication needs
+pub async fn manager_probe_replica_remove(
+    path: web::Path<(String, String)>,
+    query: web::Query<ManagerProbeReplicaRemoveRequestQuery>,
+) -> HttpResponse {
+    let (probe_id, node_id) = path.into_inner();
+
+    respond_registry_remove(remove_registry_replica(&probe_id, &node_id, &query.id))
+}
+
+fn respond_registry_upsert(result: Result<bool, RegistryError>) -> HttpResponse {
+    match result {
+        Ok(true) => HttpResponse::Created().finish(),
+        Ok(false) => HttpResponse::Ok().finish(),
+        Err(err) => respond_registry_error(err),
+    }
+}
+
+fn respond_registry_remove(result: Result<(), RegistryError>) -> HttpResponse {
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => respond_registry_error(err),
+    }
+}
+
+fn respond_registry_blocking<T>(
+    result: Result<T, BlockingError>,
+    respond: fn(T) -> HttpResponse,
+) -> HttpResponse {
+    match result {
+        Ok(result) => respond(result),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+fn respond_registry_error(err: RegistryError) -> HttpResponse {
+    warn!("prober registry change was rejected: {}", err.as_str());
+
+    match err {
+        RegistryError::NotFound => HttpResponse::NotFound().finish(),
+        RegistryError::DuplicateReplica | RegistryError::DependedOn => {
+            HttpResponse::Conflict().body(err.as_str())
+        }
+        _ => HttpResponse::BadRequest().body(err.as_str()),
+    }
+}
@@ -0,0 +1,380 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use reqwest::redirect::Policy as RedirectPolicy;
+use url::Url;
+
+use super::store::{is_public_address, Subscriber, SubscriberChannel, STORE};
+use crate::aggregator::history::Transition;
+use crate::prober::status::Status;
+use crate::APP_CONF;
+use crate::THREAD_NAME_SUBSCRIBER_DISPATCH;
+
+#[cfg(feature = "notifier-email")]
+use crate::notifier::email::send_subscriber_email;
+
+const DISPATCH_QUEUE_SIZE: usize = 1000;
+const DISPATCH_TIMEOUT_SECONDS: u64 = 10;
+
+lazy_static! {
+    static ref QUEUES: Vec<SubscriberQueue> = make_queues();
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SubscriberQueueChannel {
+    Email,
+    WebHook,
+}
+
+struct SubscriberQueue {
+    channel: SubscriberQueueChannel,
+    sender: SyncSender<SubscriberJob>,
+}
+
+#[derive(Clone)]
+enum SubscriberJob {
+    Confirm(Subscriber),
+    Announcement(String, String),
+    Status(Transition),
+}
+
+#[derive(Serialize)]
+struct WebHookPayload<'a> {
+    #[serde(rename = "type")]
+    _type: &'static str,
+
+    subject: &'a str,
+    text: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    service: Option<WebHookPayloadService<'a>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confirm_url: Option<&'a str>,
+
+    unsubscribe_url: &'a str,
+    page: WebHookPayloadPage<'a>,
+}
+
+#[derive(Serialize)]
+struct WebHookPayloadService<'a> {
+    id: &'a str,
+    label: &'a str,
+    status: Status,
+    previous: Status,
+}
+
+#[derive(Serialize)]
+struct WebHookPayloadPage<'a> {
+    title: &'a str,
+    url: &'a str,
+}
+
+impl SubscriberQueueChannel {
+    fn of(channel: &SubscriberChannel) -> Self {
+        match channel {
+            SubscriberChannel::Email(_) => SubscriberQueueChannel::Email,
+            SubscriberChannel::WebHook(_) => SubscriberQueueChannel::WebHook,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SubscriberQueueChannel::Email => "email",
+            SubscriberQueueChannel::WebHook => "webhook",
+        }
+    }
+}
+
+pub fn dispatch_confirm(subscriber: &Subscriber) {
+    enqueue(
+        Some(SubscriberQueueChannel::of(&subscriber.channel)),
+        SubscriberJob::Confirm(subscriber.clone()),
+    );
+}
+
+pub fn dispatch_announcement(title: &str, text: &str) {
+    enqueue(
+        None,
+        SubscriberJob::Announcement(title.to_owned(), text.to_owned()),
+    );
+}
+
+pub fn dispatch_transitions(transitions: &[Transition]) {
+    for transition in transitions {
+        enqueue(None, SubscriberJob::Status(transition.clone()));
+    }
+}
+
+fn make_page_url(path: &str) -> String {
+    format!(
+        "{}/{}",
+        APP_CONF.branding.page_url.as_str().trim_end_matches('/'),
+        path
+    )
+}
+
+fn make_queues() -> Vec<SubscriberQueue> {
+    let mut queues = Vec::new();
+
+    if let Some(ref subscribers) = APP_CONF.subscribers {
+        let channels = [
+            (SubscriberQueueChannel::Email, subscribers.email),
+            (SubscriberQueueChannel::WebHook, subscribers.webhook),
+        ];
+
+        // Notice: a single worker per channel delivers to all subscribers of the channel, in \
+        //   order, as deliveries to subscribers are not as urgent as notifications to the team. \
+        //   Channels have their own worker, so that slow Web Hooks do not hold emails.
+        for (channel, enabled) in channels {
+            if enabled {
+                let (sender, receiver) = mpsc::sync_channel(DISPATCH_QUEUE_SIZE);
+
+                let worker = thread::Builder::new()
+                    .name(format!(
+                        "{}-{}",
+                        THREAD_NAME_SUBSCRIBER_DISPATCH,
+                        channel.name()
+                    ))
+                    .spawn(move || run_worker(channel, receiver));
+
+                if let Err(err) = worker {
+                    error!(
+                        "could not spawn subscriber dispatch worker for channel: {} (error: {})",
+                        channel.name(),
+                        err
+                    );
+                }
+
+                queues.push(SubscriberQueue { channel, sender });
+            }
+        }
+    }
+
+    queues
+}
+
+fn enqueue(channel: Option<SubscriberQueueChannel>, job: SubscriberJob) {
+    for queue in QUEUES.iter() {
+        if (channel.is_none() || channel == Some(queue.channel))
+            && queue.sender.try_send(job.clone()).is_err()
+        {
+            error!(
+                "dropped subscriber dispatch job for channel: {} (dispatch queue is full)",
+                queue.channel.name()
+            );
+        }
+    }
+}
+
+fn run_worker(channel: SubscriberQueueChannel, receiver: Receiver<SubscriberJob>) {
+    while let Ok(job) = receiver.recv() {
+        match job {
+            SubscriberJob::Confirm(subscriber) => {
+                let confirm_url =
+                    make_page_url(&format!("subscribe/confirm/{}", subscriber.confirm_token));
+
+                let subject = format!(
+                    "Confirm your subscription to: {}",
+                    APP_CONF.branding.page_title
+                );
+                let text = format!(
+                    "You asked to receive status updates from {}. Please confirm your \
+                        subscription, or ignore this message if you did not ask for it.",
+                    APP_CONF.branding.page_title
+                );
+
+                deliver(
+                    &subscriber,
+                    "confirm",
+                    &subject,
+                    &text,
+                    None,
+                    ("Confirm subscription", &confirm_url),
+                );
+            }
+            SubscriberJob::Announcement(title, text) => {
+                let page_url = APP_CONF.branding.page_url.to_string();
+
+                for subscriber in list_recipients(channel, None) {
+                    deliver(
+                        &subscriber,
+                        "announcement",
+                        &title,
+                        &text,
+                        None,
+                        ("View status page", &page_url),
+                    );
+                }
+            }
+            SubscriberJob::Status(transition) => {
+                let page_url = make_page_url(&format!("#probe-{}", transition.probe_id));
+
+                let subject = format!(
+                    "{} is now {}",
+                    transition.probe_label,
+                    transition.to.as_str()
+                );
+                let text = format!(
+                    "The status of {} changed from {} to {}.",
+                    transition.probe_label,
+                    transition.from.as_str(),
+                    transition.to.as_str()
+                );
+
+                for subscriber in list_recipients(channel, Some(&transition.probe_id)) {
+                    deliver(
+                        &subscriber,
+                        "status",
+                        &subject,
+                        &text,
+                        Some(WebHookPayloadService {
+                            id: &transition.probe_id,
+                            label: &transition.probe_label,
+                            status: transition.to,
+                            previous: transition.from,
+                        }),
+                        ("View status page", &page_url),
+                    );
+                }
+            }
+        }
+    }
+
+    warn!(
+        "subscriber dispatch worker stopped for channel: {}",
+        channel.name()
+    );
+}
+
+fn list_recipients(channel: SubscriberQueueChannel, probe_id: Option<&str>) -> Vec<Subscriber> {
+    STORE
+        .read()
+        .unwrap()
+        .subscribers
+        .iter()
+        .filter(|subscriber| {
+            subscriber.confirmed
+                && SubscriberQueueChannel::of(&subscriber.channel) == channel
+                && subscriber.wants(probe_id)
+        })
+        .cloned()
+        .collect()
+}
+
+fn deliver(
+    subscriber: &Subscriber,
+    kind: &'static str,
+    subject: &str,
+    text: &str,
+    service: Option<WebHookPayloadService>,
+    link: (&str, &str),
+) {
+    let unsubscribe_url = make_page_url(&format!(
+        "subscribe/unsubscribe/{}",
+        subscriber.unsubscribe_token
+    ));
+
+    debug!("delivering {} to subscriber: {}", kind, subscriber.id);
+
+    let result = match subscriber.channel {
+        SubscriberChannel::Email(ref address) => {
+            deliver_email(address, subject, text, link, &unsubscribe_url)
+        }
+        SubscriberChannel::WebHook(ref url) => deliver_webhook(
+            url,
+            &WebHookPayload {
+                _type: kind,
+                subject,
+                text,
+                service,
+                confirm_url: if kind == "confirm" {
+                    Some(link.1)
+                } else {
+                    None
+                },
+                unsubscribe_url: &unsubscribe_url,
+                page: WebHookPayloadPage {
+                    title: APP_CONF.branding.page_title.as_str(),
+                    url: APP_CONF.branding.page_url.as_str(),
+                },
+            },
+        ),
+    };
+
+    if result.is_err() {
+        warn!(
+            "failed delivering {} to subscriber: {}",
+            kind, subscriber.id
+        );
+    }
+}
+
+#[cfg(feature = "notifier-email")]
+fn deliver_email(
+    address: &str,
+    subject: &str,
+    text: &str,
+    link: (&str, &str),
+    unsubscribe_url: &str,
+) -> Result<(), ()> {
+    match APP_CONF
+        .notify
+        .as_ref()
+        .and_then(|notify| notify.email.as_ref())
+    {
+        Some(email_config) => {
+            send_subscriber_email(email_config, address, subject, text, link, unsubscribe_url)
+        }
+        None => Err(()),
+    }
+}
+
+#[cfg(not(feature = "notifier-email"))]
+fn deliver_email(_: &str, _: &str, _: &str, _: (&str, &str), _: &str) -> Result<(), ()> {
+    Err(())
+}
+
+fn deliver_webhook(url: &str, payload: &WebHookPayload) -> Result<(), ()> {
+    match make_webhook_client(url)?.post(url).json(payload).send() {
+        Ok(response) if response.status().is_success() => Ok(()),
+        _ => Err(()),
+    }
+}
+
+fn make_webhook_client(url: &str) -> Result<Client, ()> {
+    let url = Url::parse(url).or(Err(()))?;
+    let host = url.host_str().ok_or(())?;
+
+    // Notice: Web Hook URLs are submitted by visitors, thus they must not reach internal \
+    //   services. The host is resolved there and the request is pinned to its addresses, so \
+    //   that the host cannot resolve to another address upon request (ie. DNS rebinding). \
+    //   Redirects are not followed, as they could lead to internal services.
+    let addresses = url.socket_addrs(|| None).or(Err(()))?;
+
+    if addresses.is_empty()
+        || addresses
+            .iter()
+            .any(|address| !is_public_address(&address.ip()))
+    {
+        warn!("refused to deliver to non-public web hook host: {}", host);
+
+        return Err(());
+    }
+
+    Client::builder()
+        .timeout(Duration::from_secs(DISPATCH_TIMEOUT_SECONDS))
+        .gzip(true)
+        .redirect(RedirectPolicy::none())
+        .resolve_to_addrs(host, &addresses)
+        .build()
+        .or(Err(()))
+}
@@ -0,0 +1,537 @@
+// Vigil
+//
+// Microservices Status Page
+// Copyright: 2026, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::config::config::ConfigSubscribers;
+use crate::APP_CONF;
+
+const EMAIL_MAXIMUM_LENGTH: usize = 254;
+const PENDING_EXPIRE_SECONDS: u64 = 86400;
+
+lazy_static! {
+    pub static ref STORE: Arc<RwLock<Store>> = Arc::new(RwLock::new(Store::default()));
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Store {
+    pub subscribers: Vec<Subscriber>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Subscriber {
+    pub id: String,
+    pub confirm_token: String,
+    pub unsubscribe_token: String,
+    pub channel: SubscriberChannel,
+    pub services: Vec<String>,
+    pub confirmed: bool,
+    pub created: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum SubscriberChannel {
+    #[serde(rename = "email")]
+    Email(String),
+
+    #[serde(rename = "webhook")]
+    WebHook(String),
+}
+
+pub enum SubscribeError {
+    Disabled,
+    Full,
+}
+
+impl Store {
+    fn subscribe(
+        &mut self,
+        config: &ConfigSubscribers,
+        channel: SubscriberChannel,
+        services: Vec<String>,
+        now: u64,
+    ) -> Result<Subscriber, SubscribeError> {
+        if !channel.enabled(config) {
+            return Err(SubscribeError::Disabled);
+        }
+
+        // Drop expired pending subscriptions, and any previous pending subscription for the \
+        //   same channel (a confirmed subscription for the same channel is kept until the new \
+        //   one gets confirmed, so that anyone cannot replace the subscription of someone else)
+        self.subscribers.retain(|subscriber| {
+            !is_pending_expired(subscriber, now)
+                && (subscriber.confirmed || subscriber.channel != channel)
+        });
+
+        // Notice: only confirmed subscriptions count towards the maximum, as pending ones may \
+        //   come from anyone. Pending subscriptions are limited to the same maximum, the oldest \
+        //   pending subscription being evicted when full (rather than refusing new ones).
+        let (confirmed_count, pending_count) =
+            self.subscribers
+                .iter()
+                .fold((0, 0), |(confirmed, pending), subscriber| {
+                    if subscriber.confirmed {
+                        (confirmed + 1, pending)
+                    } else {
+                        (confirmed, pending + 1)
+                    }
+                });
+
+        let is_replacing = self
+            .subscribers
+            .iter()
+            .any(|subscriber| subscriber.channel == channel);
+
+        if confirmed_count >= config.maximum && !is_replacing {
+            return Err(SubscribeError::Full);
+        }
+
+        if pending_count >= config.maximum {
+            if let Some(oldest_index) = self
+                .subscribers
+                .iter()
+                .position(|subscriber| !subscriber.confirmed)
+            {
+                self.subscribers.remove(oldest_index);
+            }
+        }
+
+        let subscriber = Subscriber {
+            id: Uuid::new_v4().hyphenated().to_string(),
+
+            // Notice: confirm and unsubscribe links hold different tokens, so that a forwarded \
+            //   confirmation link cannot be used to unsubscribe (and the other way around).
+            confirm_token: Uuid::new_v4().simple().to_string(),
+            unsubscribe_token: Uuid::new_v4().simple().to_string(),
+            channel,
+            services,
+            confirmed: false,
+            created: now,
+        };
+
+        self.subscribers.push(subscriber.clone());
+
+        Ok(subscriber)
+    }
+
+    fn can_confirm(&self, token: &str, now: u64) -> bool {
+        // Confirmation links that have expired are as if they did not exist
+        self.subscribers.iter().any(|subscriber| {
+            subscriber.confirm_token == token && !is_pending_expired(subscriber, now)
+        })
+    }
+
+    fn can_unsubscribe(&self, token: &str) -> bool {
+        self.subscribers
+            .iter()
+            .any(|subscriber| subscriber.unsubscribe_token == token)
+    }
+
+    fn confirm(&mut self, config: &ConfigSubscribers, token: &str, now: u64) -> Option<Subscriber> {
+        let mut subscriber = self
+            .subscribers
+            .iter()
+            .find(|subscriber| subscriber.confirm_token == token)?
+            .clone();
+
+        // Confirmation link has expired? (as if it did not exist)
+        if is_pending_expired(&subscriber, now) {
+            return None;
+        }
+
+        // Maximum was reached since this subscription was requested? (refuse it, unless it \
+        //   replaces a confirmed subscription for the same channel)
+        let confirmed_count = self
+            .subscribers
+            .iter()
+            .filter(|other_subscriber| {
+                other_subscriber.confirmed && other_subscriber.channel != subscriber.channel
+            })
+            .count();
+
+        if confirmed_count >= config.maximum {
+            return None;
+        }
+
+        subscriber.confirmed = true;
+
+        // Replace any previous subscription for the same channel (the subscriber changed \
+        //   choices)
+        self.subscribers
+            .retain(|other_subscriber| other_subscriber.channel != subscriber.channel);
+
+        self.subscribers.push(subscriber.clone());
+
+        Some(subscriber)
+    }
+
+    fn unsubscribe(&mut self, token: &str) -> Option<Subscriber> {
+        let subscriber_index = self
+            .subscribers
+            .iter()
+            .position(|subscriber| subscriber.unsubscribe_token == token)?;
+
+        Some(self.subscribers.remove(subscriber_index))
+    }
+}
+
+impl Subscriber {
+    pub fn wants(&self, probe_id: Option<&str>) -> bool {
+        // Subscribers with no service selected get updates for all services (and updates that \
+        //   do not relate to a specific service, eg. announcements are sent to all subscribers)
+        match probe_id {
+            Some(probe_id) => {
+                self.services.is_empty() || self.services.iter().any(|service| service == probe_id)
+            }
+            None => true,
+        }
+    }
+}
+
+impl SubscriberChannel {
+    pub fn from_form(kind: &str, target: &str) -> Option<Self> {
+        match kind {
+            "email" => {
+                // Notice: email addresses are only roughly validated there, as they get \
+                //   validated for good when the confirmation email is sent.
+                if target.len() <= EMAIL_MAXIMUM_LENGTH
+                    && target.contains('@')
+                    && !target.contains(char::is_whitespace)
+                {
+                    Some(SubscriberChannel::Email(target.to_owned()))
+                } else {
+                    None
+                }
+            }
+            "webhook" => match Url::parse(target) {
+                Ok(url)
+                    if (url.scheme() == "http" || url.scheme() == "https")
+                        && is_public_host(&url) =>
+                {
+                    Some(SubscriberChannel::WebHook(url.to_string()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn enabled(&self, config: &ConfigSubscribers) -> bool {
+        match self {
+            SubscriberChannel::Email(_) => config.email,
+            SubscriberChannel::WebHook(_) => config.webhook,
+        }
+    }
+}
+
+pub fn is_public_address(address: &IpAddr) -> bool {
+    // Notice: this rejects addresses that may reach internal services, so that visitors cannot \
+    //   make Vigil send requests to its own network via Web Hook subscriptions.
+    match address {
+        IpAddr::V4(address) => is_public_address_v4(address),
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => is_public_address_v4(&address),
+            None => is_public_address_v6(address),
+        },
+    }
+}
+
+fn is_public_address_v4(address: &Ipv4Addr) -> bool {
+    let octets = address.octets();
+
+    // Notice: '0.0.0.0/8' is the current network, and '100.64.0.0/10' is the shared address \
+    //   space (ie. carrier-grade NAT)
+    !(address.is_private()
+        || address.is_loopback()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_unspecified()
+        || address.is_multicast()
+        || octets[0] == 0
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+}
+
+fn is_public_address_v6(address: &Ipv6Addr) -> bool {
+    let segments = address.segments();
+
+    // Notice: 'fc00::/7' are unique local addresses, and 'fe80::/10' are link-local addresses
+    !(address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80)
+}
+
+fn is_public_host(url: &Url) -> bool {
+    // Notice: host names are checked once resolved, upon delivery (as they may resolve to \
+    //   different addresses over time).
+    match url.host() {
+        Some(Host::Ipv4(address)) => is_public_address(&IpAddr::V4(address)),
+        Some(Host::Ipv6(address)) => is_public_address(&IpAddr::V6(address)),
+        Some(Host::Domain(domain)) => domain != "localhost" && !domain.ends_with(".localhost"),
+        None => false,
+    }
+}
+
+fn time_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn is_pending_expired(subscriber: &Subscriber, now: u64) -> bool {
+    !subscriber.confirmed && subscriber.created + PENDING_EXPIRE_SECONDS <= now
+}
+
+pub fn load() {
+    if let Some(ref subscribers) = APP_CONF.subscribers {
+        match fs::read_to_string(&subscribers.store_path) {
+            Ok(contents) => {
+                let loaded_store: Store = serde_json::from_str(&contents).unwrap_or_else(|err| {
+                    panic!(
+                        "could not parse subscribers store: {:?} ({})",
+                        subscribers.store_path, err
+                    )
+                });
+
+                info!(
+                    "loaded {} subscribers from store",
+                    loaded_store.subscribers.len()
+                );
+
+                *STORE.write().unwrap() = loaded_store;
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                info!("subscribers store does not exist yet, starting empty");
+            }
+            Err(err) => panic!(
+                "could not read subscribers store: {:?} ({})",
+                subscribers.store_path, err
+            ),
+        }
+    }
+}
+
+pub fn subscribe(
+    channel: SubscriberChannel,
+    services: Vec<String>,
+) -> Result<Subscriber, SubscribeError> {
+    let config = APP_CONF
+        .subscribers
+        .as_ref()
+        .ok_or(SubscribeError::Disabled)?;
+
+    let mut store = STORE.write().unwrap();
+
+    let subscriber = store.subscribe(config, channel, services, time_now())?;
+
+    save(&store);
+
+    Ok(subscriber)
+}
+
+pub fn can_confirm(token: &str) -> bool {
+    STORE.read().unwrap().can_confirm(token, time_now())
+}
+
+pub fn can_unsubscribe(token: &str) -> bool {
+    STORE.read().unwrap().can_unsubscribe(token)
+}
+
+pub fn confirm(token: &str) -> Option<Subscriber> {
+    let config = APP_CONF.subscribers.as_ref()?;
+
+    let mut store = STORE.write().unwrap();
+
+    let subscriber = store.confirm(config, token, time_now())?;
+
+    save(&store);
+
+    Some(subscriber)
+}
+
+pub fn unsubscribe(token: &str) -> Option<Subscriber> {
+    let mut store = STORE.write().unwrap();
+
+    let subscriber = store.unsubscribe(token)?;
+
+    save(&store);
+
+    Some(subscriber)
+}
+
+fn save(store: &Store) {
+    if let Some(ref subscribers) = APP_CONF.subscribers {
+        // Notice: write to a temporary file first, then move it over the store file, so that \
+        //   the store file never ends up half-written (eg. if Vigil gets killed while saving).
+        let temporary_path = subscribers.store_path.with_extension("tmp");
+
+        let result = serde_json::to_string(store)
+            .map_err(io::Error::from)
+            .and_then(|contents| fs::write(&temporary_path, contents))
+            .and_then(|_| fs::rename(&temporary_path, &subscribers.store_path));
+
+        if let Err(err) = result {
+            error!(
+                "could not save subscribers store: {:?} ({})",
+                subscribers.store_path, err
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_config(maximum: usize) -> ConfigSubscribers {
+        toml::from_str(&format!("maximum = {}", maximum)).unwrap()
+    }
+
+    fn make_email(address: &str) -> SubscriberChannel {
+        SubscriberChannel::Email(address.to_string())
+    }
+
+    #[test]
+    fn it_confirms_with_confirm_token_only() {
+        let (config, mut store) = (make_config(10), Store::default());
+
+        let subscriber = store
+            .subscribe(&config, make_email("a@example.com"), Vec::new(), 0)
+            .ok()
+            .unwrap();
+
+        assert_ne!(subscriber.confirm_token, subscriber.unsubscribe_token);
+
+        assert!(!store.can_confirm(&subscriber.unsubscribe_token, 0));
+        assert!(store
+            .confirm(&config, &subscriber.unsubscribe_token, 0)
+            .is_none());
+
+        assert!(store.can_confirm(&subscriber.confirm_token, 0));
+        assert!(
+            store
+                .confirm(&config, &subscriber.confirm_token, 0)
+                .unwrap()
+                .confirmed
+        );
+
+        // Confirmation token cannot be used to unsubscribe
+        assert!(!store.can_unsubscribe(&subscriber.confirm_token));
+        assert!(store.unsubscribe(&subscriber.confirm_token).is_none());
+
+        assert!(store.unsubscribe(&subscriber.unsubscribe_token).is_some());
+        assert!(store.subscribers.is_empty());
+    }
+
+    #[test]
+    fn it_expires_pending_subscriptions() {
+        let (config, mut store) = (make_config(10), Store::default());
+
+        let subscriber = store
+            .subscribe(&config, make_email("a@example.com"), Vec::new(), 0)
+            .ok()
+            .unwrap();
+
+        assert!(store.can_confirm(&subscriber.confirm_token, PENDING_EXPIRE_SECONDS - 1));
+        assert!(!store.can_confirm(&subscriber.confirm_token, PENDING_EXPIRE_SECONDS));
+        assert!(store
+            .confirm(&config, &subscriber.confirm_token, PENDING_EXPIRE_SECONDS)
+            .is_none());
+
+        // Expired pending subscriptions are dropped upon next subscription
+        store
+            .subscribe(
+                &config,
+                make_email("b@example.com"),
+                Vec::new(),
+                PENDING_EXPIRE_SECONDS,
+            )
+            .ok()
+            .unwrap();
+
+        assert_eq!(store.subscribers.len(), 1);
+    }
+
+    #[test]
+    fn it_replaces_subscription_once_confirmed() {
+        let (config, mut store) = (make_config(10), Store::default());
+
+        let previous_subscriber = store
+            .subscribe(&config, make_email("a@example.com"), Vec::new(), 0)
+            .ok()
+            .unwrap();
+
+        store.confirm(&config, &previous_subscriber.confirm_token, 0);
+
+        let subscriber = store
+            .subscribe(
+                &config,
+                make_email("a@example.com"),
+                vec!["api".to_string()],
+                10,
+            )
+            .ok()
+            .unwrap();
+
+        // Previous subscription is kept until the new one gets confirmed
+        assert_eq!(store.subscribers.len(), 2);
+        assert!(store.can_unsubscribe(&previous_subscriber.unsubscribe_token));
+
+        store.confirm(&config, &subscriber.confirm_token, 10);
+
+        assert_eq!(store.subscribers.len(), 1);
+        assert_eq!(store.subscribers[0].services, ["api"]);
+        assert!(!store.can_unsubscribe(&previous_subscriber.unsubscribe_token));
+    }
+
+    #[test]
+    fn it_refuses_subscriptions_when_full() {
+        let (config, mut store) = (make_config(1), Store::default());
+
+        let subscriber = store
+            .subscribe(&config, make_email("a@example.com"), Vec::new(), 0)
+            .ok()
+            .unwrap();
+
+        store.confirm(&config, &subscriber.confirm_token, 0);
+
+        assert!(matches!(
+            store.subscribe(&config, make_email("b@example.com"), Vec::new(), 0),
+            Err(SubscribeError::Full)
+        ));
+
+        // Subscriptions replacing a confirmed subscription are still accepted
+        assert!(store
+            .subscribe(&config, make_email("a@example.com"), Vec::new(), 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn it_refuses_disabled_channels() {
+        let config: ConfigSubscribers = toml::from_str("webhook = false").unwrap();
+
+        assert!(matches!(
+            Store::default().subscribe(
+                &config,
+                SubscriberChannel::WebHook("https://example.com/hook".to_string()),
+                Vec::new(),
+                0
+            ),
+            Err(SubscribeError::Disabled)
+        ));
+    }
+}